@@ -1,5 +1,37 @@
 use crate::NArray;
-const E: f32 = std::f32::consts::E;
+use ndarray::Axis;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// Activation function provided by the user, see [`register_activation`].
+pub type ActivationFn = Arc<dyn Fn(NArray) -> NArray + Send + Sync>;
+
+fn registry() -> &'static RwLock<HashMap<String, ActivationFn>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, ActivationFn>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Makes `function` available to models whose config refers to the activation `name`, the
+/// `registered_name` Keras gives to custom functions or their plain name. Registering a name
+/// again replaces the previous function, models already loaded keep the one they found.
+pub fn register_activation<F>(name: &str, function: F)
+where
+    F: Fn(NArray) -> NArray + Send + Sync + 'static,
+{
+    registry()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_owned(), Arc::new(function));
+}
+
+/// Function registered under `name`, if any.
+pub fn get_registered_activation(name: &str) -> Option<ActivationFn> {
+    registry()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .cloned()
+}
 
 // Define sigmoid and relu functions
 fn sigmoid_scalar(x: f32) -> f32 {
@@ -14,10 +46,25 @@ fn relu_scalar(x: f32) -> f32 {
     }
 }
 
+/// Softmax along the last axis, so every sample of a batch is normalized on its own.
 pub fn softmax(z: NArray) -> NArray {
-    let exp_arr = z.mapv(|x| f32::powf(E, x));
-    let sum: f32 = exp_arr.iter().sum();
-    exp_arr.mapv(|x| x / sum)
+    let last_axis = z.ndim().saturating_sub(1);
+    softmax_axis(z, last_axis)
+}
+
+/// Softmax along `axis`. The maximum of every lane is subtracted first, so large logits
+/// don't overflow. A 0-d array has no axis and is returned unchanged.
+pub fn softmax_axis(mut z: NArray, axis: usize) -> NArray {
+    if z.ndim() == 0 {
+        return z;
+    }
+    for mut lane in z.lanes_mut(Axis(axis)) {
+        let max = lane.fold(f32::NEG_INFINITY, |max, &x| max.max(x));
+        lane.mapv_inplace(|x| (x - max).exp());
+        let sum = lane.sum();
+        lane.mapv_inplace(|x| x / sum);
+    }
+    z
 }
 
 pub fn sigmoid(z: NArray) -> NArray {
@@ -28,6 +75,118 @@ pub fn relu(z: NArray) -> NArray {
     z.mapv(relu_scalar)
 }
 
+pub fn tanh(z: NArray) -> NArray {
+    z.mapv(f32::tanh)
+}
+
+const SELU_ALPHA: f32 = 1.6732632;
+const SELU_SCALE: f32 = 1.050701;
+
+fn elu_scalar(x: f32, alpha: f32) -> f32 {
+    if x > 0.0 {
+        x
+    } else {
+        alpha * x.exp_m1()
+    }
+}
+
+/// `log(1 + exp(x))` without overflowing for large `x`.
+fn softplus_scalar(x: f32) -> f32 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
+fn hard_sigmoid_scalar(x: f32) -> f32 {
+    ((x + 3.0) / 6.0).clamp(0.0, 1.0)
+}
+
+/// Error function, Abramowitz and Stegun 7.1.26 evaluated in double precision. Its error
+/// stays below 1.5e-7, under the resolution of `f32` around the GELU outputs.
+fn erf(x: f32) -> f32 {
+    let x = f64::from(x);
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let polynomial = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - polynomial * (-x * x).exp();
+    (y.copysign(x)) as f32
+}
+
+pub fn elu(z: NArray, alpha: f32) -> NArray {
+    z.mapv(|x| elu_scalar(x, alpha))
+}
+
+pub fn selu(z: NArray) -> NArray {
+    z.mapv(|x| SELU_SCALE * elu_scalar(x, SELU_ALPHA))
+}
+
+/// Exact GELU, `x * Φ(x)` with the standard normal CDF.
+pub fn gelu(z: NArray) -> NArray {
+    z.mapv(|x| 0.5 * x * (1.0 + erf(x / std::f32::consts::SQRT_2)))
+}
+
+/// GELU with the tanh approximation, Keras' `gelu(x, approximate=True)`.
+pub fn gelu_approximate(z: NArray) -> NArray {
+    let coefficient = (2.0 / std::f32::consts::PI).sqrt();
+    z.mapv(|x| 0.5 * x * (1.0 + (coefficient * (x + 0.044715 * x.powi(3))).tanh()))
+}
+
+pub fn silu(z: NArray) -> NArray {
+    z.mapv(|x| x * sigmoid_scalar(x))
+}
+
+pub fn softplus(z: NArray) -> NArray {
+    z.mapv(softplus_scalar)
+}
+
+pub fn softsign(z: NArray) -> NArray {
+    z.mapv(|x| x / (1.0 + x.abs()))
+}
+
+/// Keras 3 definition, `relu6(x + 3) / 6`. Keras 2 used `0.2 * x + 0.5` instead.
+pub fn hard_sigmoid(z: NArray) -> NArray {
+    z.mapv(hard_sigmoid_scalar)
+}
+
+pub fn hard_silu(z: NArray) -> NArray {
+    z.mapv(|x| x * hard_sigmoid_scalar(x))
+}
+
+pub fn exponential(z: NArray) -> NArray {
+    z.mapv(f32::exp)
+}
+
+pub fn relu6(z: NArray) -> NArray {
+    z.mapv(|x| x.clamp(0.0, 6.0))
+}
+
+pub fn leaky_relu(z: NArray, negative_slope: f32) -> NArray {
+    z.mapv(|x| if x > 0.0 { x } else { negative_slope * x })
+}
+
+pub fn mish(z: NArray) -> NArray {
+    z.mapv(|x| x * softplus_scalar(x).tanh())
+}
+
+/// Logarithm of the softmax along the last axis.
+pub fn log_softmax(z: NArray) -> NArray {
+    let last_axis = z.ndim().saturating_sub(1);
+    log_softmax_axis(z, last_axis)
+}
+
+/// Logarithm of the softmax along `axis`, `x - max - log(sum(exp(x - max)))`. A 0-d array
+/// has no axis and is returned unchanged.
+pub fn log_softmax_axis(mut z: NArray, axis: usize) -> NArray {
+    if z.ndim() == 0 {
+        return z;
+    }
+    for mut lane in z.lanes_mut(Axis(axis)) {
+        let max = lane.fold(f32::NEG_INFINITY, |max, &x| max.max(x));
+        let log_sum = lane.mapv(|x| (x - max).exp()).sum().ln();
+        lane.mapv_inplace(|x| x - max - log_sum);
+    }
+    z
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{NArray, Vector};
@@ -50,6 +209,20 @@ mod tests {
     }
 
     #[test]
+    fn softmax_batch() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![0.0, 0.0, 1.0, 3.0]).unwrap();
+
+        let output = super::softmax(input);
+
+        assert_approx_eq!(output[[0, 0]], 0.5, 1e-6);
+        assert_approx_eq!(output[[0, 1]], 0.5, 1e-6);
+        assert_approx_eq!(output[[1, 0]], 0.1192029, 1e-6);
+        assert_approx_eq!(output[[1, 1]], 0.8807971, 1e-6);
+    }
+
+    #[test]
+    #[allow(clippy::excessive_precision)]
     fn sigmoid() {
         let input = NArray::from_shape_vec(ndarray::IxDyn(&[3]), vec![1.0, 0.0, -1.0]).unwrap();
 
@@ -78,4 +251,151 @@ mod tests {
             assert_approx_eq!(actual, expected, 1e-6);
         }
     }
+
+    fn assert_activation(activation: fn(NArray) -> NArray, expected: [f32; 4]) {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[4]), vec![-2.0, -0.5, 0.0, 1.5]).unwrap();
+
+        let output = activation(input);
+
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn tanh() {
+        assert_activation(super::tanh, [-0.9640276, -0.4621172, 0.0, 0.9051482]);
+    }
+
+    #[test]
+    fn elu() {
+        assert_activation(|z| super::elu(z, 1.0), [-0.8646647, -0.3934693, 0.0, 1.5]);
+    }
+
+    #[test]
+    fn selu() {
+        assert_activation(super::selu, [-1.520166, -0.6917582, 0.0, 1.576051]);
+    }
+
+    #[test]
+    fn gelu() {
+        assert_activation(super::gelu, [-0.04550026, -0.1542688, 0.0, 1.399789]);
+    }
+
+    #[test]
+    fn gelu_approximate() {
+        assert_activation(
+            super::gelu_approximate,
+            [-0.04540231, -0.154286, 0.0, 1.399572],
+        );
+    }
+
+    #[test]
+    fn silu() {
+        assert_activation(super::silu, [-0.2384058, -0.1887703, 0.0, 1.226362]);
+    }
+
+    #[test]
+    fn softplus() {
+        assert_activation(
+            super::softplus,
+            [0.126928, 0.474077, std::f32::consts::LN_2, 1.701413],
+        );
+    }
+
+    #[test]
+    fn softsign() {
+        assert_activation(super::softsign, [-0.6666667, -0.3333333, 0.0, 0.6]);
+    }
+
+    #[test]
+    fn hard_sigmoid() {
+        assert_activation(super::hard_sigmoid, [0.1666667, 0.4166667, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn hard_silu() {
+        assert_activation(super::hard_silu, [-0.3333333, -0.2083333, 0.0, 1.125]);
+    }
+
+    #[test]
+    fn exponential() {
+        assert_activation(super::exponential, [0.1353353, 0.6065307, 1.0, 4.481689]);
+    }
+
+    #[test]
+    fn relu6() {
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[3]), vec![-1.0, 3.0, 7.0]).unwrap();
+
+        assert_eq!(super::relu6(input).as_slice().unwrap(), &[0.0, 3.0, 6.0]);
+    }
+
+    #[test]
+    fn leaky_relu() {
+        assert_activation(|z| super::leaky_relu(z, 0.2), [-0.4, -0.1, 0.0, 1.5]);
+    }
+
+    #[test]
+    fn mish() {
+        assert_activation(super::mish, [-0.2525015, -0.2207438, 0.0, 1.403378]);
+    }
+
+    #[test]
+    fn log_softmax() {
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[3]), vec![1.0, 2.0, 3.0]).unwrap();
+
+        let output = super::log_softmax(input);
+
+        let expected = [-2.407606, -1.407606, -0.4076059];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn softmax_extreme_logits() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![1000.0, 1000.0, -1000.0, 0.0])
+                .unwrap();
+
+        let output = super::softmax(input);
+
+        assert_approx_eq!(output[[0, 0]], 0.5, 1e-6);
+        assert_approx_eq!(output[[0, 1]], 0.5, 1e-6);
+        assert_approx_eq!(output[[1, 0]], 0.0, 1e-6);
+        assert_approx_eq!(output[[1, 1]], 1.0, 1e-6);
+    }
+
+    #[test]
+    fn softmax_first_axis() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![0.0, 1.0, 0.0, 3.0]).unwrap();
+
+        let output = super::softmax_axis(input, 0);
+
+        assert_approx_eq!(output[[0, 0]], 0.5, 1e-6);
+        assert_approx_eq!(output[[1, 0]], 0.5, 1e-6);
+        assert_approx_eq!(output[[0, 1]], 0.1192029, 1e-6);
+        assert_approx_eq!(output[[1, 1]], 0.8807971, 1e-6);
+    }
+
+    #[test]
+    fn softmax_scalar() {
+        let input = NArray::from_elem(ndarray::IxDyn(&[]), 2.0);
+
+        assert_eq!(super::softmax(input.clone()), input);
+        assert_eq!(super::log_softmax(input.clone()), input);
+    }
+
+    #[test]
+    fn log_softmax_extreme_logits() {
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[2]), vec![-500.0, 500.0]).unwrap();
+
+        let output = super::log_softmax(input);
+
+        assert_approx_eq!(output[[0]], -1000.0, 1e-3);
+        assert_approx_eq!(output[[1]], 0.0, 1e-6);
+        assert!(output.iter().all(|x| x.is_finite()));
+    }
 }
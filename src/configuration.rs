@@ -1,20 +1,59 @@
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+pub mod layers;
+
+use layers::{ConfigDeserializer, LayerConfigError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::collections::HashMap;
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Config {
-    module: String,
+    module: Option<String>,
     class_name: String,
     config: InnerConfig,
     registered_name: Option<String>,
     build_config: Option<HashMap<String, Value>>,
-    compile_config: CompileConfig,
+    compile_config: Option<CompileConfig>,
 }
 
 impl Config {
+    pub fn get_class_name(&self) -> &str {
+        &self.class_name
+    }
+
     pub fn get_layers(&self) -> &Vec<Layer> {
-        &self.config.get_layers()
+        self.config.get_layers()
+    }
+
+    pub fn get_input_layers(&self) -> Vec<TensorReference> {
+        self.config.get_input_layers()
+    }
+
+    pub fn get_output_layers(&self) -> Vec<TensorReference> {
+        self.config.get_output_layers()
+    }
+}
+
+/// Content of `metadata.json` stored next to `config.json` in a `.keras` archive.
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Metadata {
+    keras_version: String,
+    date_saved: Option<String>,
+}
+
+impl Metadata {
+    pub fn new(keras_version: String, date_saved: Option<String>) -> Self {
+        Metadata {
+            keras_version,
+            date_saved,
+        }
+    }
+
+    pub fn get_keras_version(&self) -> &str {
+        &self.keras_version
+    }
+
+    pub fn get_date_saved(&self) -> Option<&str> {
+        self.date_saved.as_deref()
     }
 }
 
@@ -40,6 +79,8 @@ impl CompileConfig {
 pub struct InnerConfig {
     name: String,
     layers: Vec<Layer>,
+    input_layers: Option<Value>,
+    output_layers: Option<Value>,
 }
 
 impl InnerConfig {
@@ -47,26 +88,178 @@ impl InnerConfig {
         &self.layers
     }
 
+    /// Inputs of a functional model, empty for sequential models.
+    pub fn get_input_layers(&self) -> Vec<TensorReference> {
+        self.input_layers
+            .as_ref()
+            .map(TensorReference::collect)
+            .unwrap_or_default()
+    }
+
+    /// Outputs of a functional model, empty for sequential models.
+    pub fn get_output_layers(&self) -> Vec<TensorReference> {
+        self.output_layers
+            .as_ref()
+            .map(TensorReference::collect)
+            .unwrap_or_default()
+    }
+
     #[allow(dead_code)]
     pub fn new(name: String, layers: Vec<Layer>) -> Self {
-        InnerConfig { name, layers }
+        InnerConfig {
+            name,
+            layers,
+            input_layers: None,
+            output_layers: None,
+        }
+    }
+}
+
+/// Output tensor of a layer call, serialized by Keras as `[layer_name, node_index, tensor_index]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TensorReference {
+    layer_name: String,
+    node_index: usize,
+    tensor_index: usize,
+}
+
+impl TensorReference {
+    pub fn new(layer_name: String, node_index: usize, tensor_index: usize) -> Self {
+        TensorReference {
+            layer_name,
+            node_index,
+            tensor_index,
+        }
+    }
+
+    pub fn get_layer_name(&self) -> &str {
+        &self.layer_name
+    }
+
+    pub fn get_node_index(&self) -> usize {
+        self.node_index
+    }
+
+    pub fn get_tensor_index(&self) -> usize {
+        self.tensor_index
+    }
+
+    /// Parses a single `[layer_name, node_index, tensor_index, ...]` entry.
+    fn from_value(value: &Value) -> Option<Self> {
+        match value.as_array()?.as_slice() {
+            [layer_name, node_index, tensor_index, ..] => Some(TensorReference::new(
+                layer_name.as_str()?.to_owned(),
+                node_index.as_u64()? as usize,
+                tensor_index.as_u64()? as usize,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Collects every tensor referenced in `value`, in order of appearance.
+    ///
+    /// Understands both the Keras 2 form (nested lists of `[name, node, tensor, kwargs]`) and
+    /// the Keras 3 one (`__keras_tensor__` objects carrying a `keras_history`).
+    pub fn collect(value: &Value) -> Vec<Self> {
+        let mut references = Vec::new();
+        Self::collect_into(value, &mut references);
+        references
+    }
+
+    fn collect_into(value: &Value, references: &mut Vec<Self>) {
+        match value {
+            Value::Object(map) => {
+                if map.get("class_name").and_then(Value::as_str) == Some("__keras_tensor__") {
+                    references.extend(Self::from_value(&value["config"]["keras_history"]));
+                } else {
+                    map.values()
+                        .for_each(|item| Self::collect_into(item, references));
+                }
+            }
+            Value::Array(items) => match Self::from_value(value) {
+                Some(reference) => {
+                    // Keras 2 keeps the keyword arguments of the call after the reference.
+                    references.push(reference);
+                    items[3..]
+                        .iter()
+                        .for_each(|item| Self::collect_into(item, references));
+                }
+                None => items
+                    .iter()
+                    .for_each(|item| Self::collect_into(item, references)),
+            },
+            _ => {}
+        }
     }
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum LayerType {
+    Activation,
+    ActivityRegularization,
+    Add,
+    AlphaDropout,
+    Average,
+    AveragePooling1D,
+    AveragePooling2D,
+    BatchNormalization,
+    Bidirectional,
+    Concatenate,
+    Conv2D,
     Dense,
+    Dot,
+    Dropout,
+    #[serde(rename = "ELU")]
+    Elu,
+    Embedding,
     Flatten,
+    GaussianDropout,
+    GaussianNoise,
+    GlobalAveragePooling1D,
+    GlobalAveragePooling2D,
+    GlobalMaxPooling1D,
+    GlobalMaxPooling2D,
+    GroupNormalization,
+    #[serde(rename = "GRU")]
+    Gru,
     InputLayer,
+    LayerNormalization,
+    #[serde(rename = "LeakyReLU")]
+    LeakyRelu,
+    #[serde(rename = "LSTM")]
+    Lstm,
+    MaxPooling1D,
+    MaxPooling2D,
+    Maximum,
+    Minimum,
+    MultiHeadAttention,
+    Multiply,
+    Permute,
+    #[serde(rename = "PReLU")]
+    PRelu,
+    #[serde(rename = "ReLU")]
+    Relu,
+    RepeatVector,
+    Reshape,
+    #[serde(rename = "SimpleRNN")]
+    SimpleRnn,
+    Softmax,
+    SpatialDropout1D,
+    SpatialDropout2D,
+    SpatialDropout3D,
+    Subtract,
+    TimeDistributed,
 }
 
 #[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Layer {
-    module: String,
+    module: Option<String>,
     class_name: LayerType,
     config: HashMap<String, Value>,
     registered_name: Option<String>,
     build_config: Option<HashMap<String, Value>>,
+    name: Option<String>,
+    inbound_nodes: Option<Vec<Value>>,
 }
 
 impl Layer {
@@ -74,12 +267,79 @@ impl Layer {
         &self.class_name
     }
 
-    pub fn get_property(&self, property_name: &str) -> &Value {
-        &self.config[property_name]
+    pub fn get_name(&self) -> Option<&str> {
+        self.name
+            .as_deref()
+            .or_else(|| self.config.get("name").and_then(Value::as_str))
+    }
+
+    /// Input shape Keras recorded when building the layer, `None` for unknown sizes such as
+    /// the batch. Keras 2 configs don't have it.
+    pub fn get_input_shape(&self) -> Option<Vec<Option<usize>>> {
+        let input_shape = self.build_config.as_ref()?.get("input_shape")?;
+        serde_json::from_value(input_shape.clone()).ok()
+    }
+
+    /// Batched input shape set on an `InputLayer` or on the first layer of a sequential
+    /// model, `batch_shape` in Keras 3 and `batch_input_shape` in Keras 2.
+    pub fn get_batch_input_shape(&self) -> Option<Vec<Option<usize>>> {
+        let shape = self
+            .config
+            .get("batch_shape")
+            .or_else(|| self.config.get("batch_input_shape"))?;
+        serde_json::from_value(shape.clone()).ok()
+    }
+
+    /// Inputs of every call of the layer in a functional model, one entry per node.
+    pub fn get_inbound_nodes(&self) -> Vec<Vec<TensorReference>> {
+        self.inbound_nodes
+            .iter()
+            .flatten()
+            .map(TensorReference::collect)
+            .collect()
+    }
+
+    /// Keyword arguments of every call of the layer, one entry per node. Tensors passed as
+    /// keywords come after the positional ones in [`Layer::get_inbound_nodes`], sorted by name.
+    pub fn get_call_keywords(&self) -> Vec<Map<String, Value>> {
+        self.inbound_nodes
+            .iter()
+            .flatten()
+            .map(|node| match node {
+                Value::Object(node) => node
+                    .get("kwargs")
+                    .and_then(Value::as_object)
+                    .cloned()
+                    .unwrap_or_default(),
+                Value::Array(entries) => entries
+                    .iter()
+                    .filter_map(|entry| entry.get(3).and_then(Value::as_object))
+                    .flat_map(|keywords| keywords.clone())
+                    .collect(),
+                _ => Map::new(),
+            })
+            .collect()
+    }
+
+    /// Parses the `config` of the layer into the typed config of its type, such as
+    /// [`layers::DenseConfig`]. Fields the type doesn't know are logged as warnings.
+    pub fn parse_config<T: DeserializeOwned>(&self) -> Result<T, LayerConfigError> {
+        let layer = self.get_name().unwrap_or("<unnamed>");
+        T::deserialize(ConfigDeserializer::new(layer, &self.config)).map_err(|source| {
+            LayerConfigError::new(layer.to_owned(), self.get_class_name_str(), source)
+        })
+    }
+
+    /// Name of the layer type as Keras writes it, `GRU` rather than `Gru`.
+    pub fn get_class_name_str(&self) -> String {
+        match serde_json::to_value(&self.class_name) {
+            Ok(Value::String(class_name)) => class_name,
+            _ => format!("{:?}", self.class_name),
+        }
     }
 
     pub fn new(
-        module: String,
+        module: Option<String>,
         class_name: LayerType,
         config: HashMap<String, Value>,
         registered_name: Option<String>,
@@ -91,6 +351,8 @@ impl Layer {
             config,
             registered_name,
             build_config,
+            name: None,
+            inbound_nodes: None,
         }
     }
 }
@@ -213,12 +475,12 @@ mod tests {
         metric_config.insert(String::from("dtype"), serde_json::json!("float32"));
 
         let config = Config {
-            module: String::from("keras"),
+            module: Some(String::from("keras")),
             class_name: String::from("Sequential"),
             config: InnerConfig::new(
                 String::from("sequential"),
                 vec![Layer::new(
-                    String::from("keras.layers"),
+                    Some(String::from("keras.layers")),
                     LayerType::InputLayer,
                     input_layer_config,
                     None,
@@ -227,7 +489,7 @@ mod tests {
             ),
             registered_name: None,
             build_config: None,
-            compile_config: CompileConfig::new(
+            compile_config: Some(CompileConfig::new(
                 Optimizer::new(
                     String::from("keras.optimizers"),
                     String::from("Adam"),
@@ -246,7 +508,7 @@ mod tests {
                     metric_config,
                     None,
                 )],
-            ),
+            )),
         };
 
         let serialized = serde_json::to_string(&config).unwrap();
@@ -255,4 +517,149 @@ mod tests {
 
         assert_eq!(config, deserialized);
     }
+
+    #[test]
+    fn test_legacy_config_deserialization() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "class_name": "Sequential",
+                "config": {
+                    "name": "sequential",
+                    "layers": [
+                        {"class_name": "Flatten", "config": {"name": "flatten", "batch_input_shape": [null, 28, 28]}},
+                        {"class_name": "Dense", "config": {"name": "dense", "units": 10, "activation": "softmax"}}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let layers = config.get_layers();
+        assert_eq!(layers.len(), 2);
+        assert_eq!(layers[1].get_class_name(), &LayerType::Dense);
+        assert_eq!(layers[1].get_name(), Some("dense"));
+        assert_eq!(
+            layers[0].get_batch_input_shape(),
+            Some(vec![None, Some(28), Some(28)])
+        );
+    }
+
+    #[test]
+    fn test_regularization_layers_deserialization() {
+        let layers: Vec<Layer> = serde_json::from_str(
+            r#"[
+                {"class_name": "Dropout", "config": {"name": "dropout", "rate": 0.5}},
+                {"class_name": "SpatialDropout2D", "config": {"name": "spatial_dropout2d", "rate": 0.2}},
+                {"class_name": "GaussianNoise", "config": {"name": "gaussian_noise", "stddev": 0.1}},
+                {"class_name": "ActivityRegularization", "config": {"name": "activity_regularization", "l1": 0.01}}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(layers[0].get_class_name(), &LayerType::Dropout);
+        assert_eq!(layers[1].get_class_name(), &LayerType::SpatialDropout2D);
+        assert_eq!(layers[2].get_class_name(), &LayerType::GaussianNoise);
+        assert_eq!(
+            layers[3].get_class_name(),
+            &LayerType::ActivityRegularization
+        );
+    }
+
+    #[test]
+    fn test_functional_config_deserialization() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "module": "keras.src.models.functional",
+                "class_name": "Functional",
+                "config": {
+                    "name": "functional",
+                    "layers": [
+                        {"module": "keras.layers", "class_name": "InputLayer", "config": {"name": "input_layer"}, "registered_name": null, "name": "input_layer", "inbound_nodes": []},
+                        {"module": "keras.layers", "class_name": "Dense", "config": {"name": "dense"}, "registered_name": null, "build_config": null, "name": "dense", "inbound_nodes": [
+                            {"args": [{"class_name": "__keras_tensor__", "config": {"shape": [null, 4], "dtype": "float32", "keras_history": ["input_layer", 0, 0]}}], "kwargs": {}}
+                        ]}
+                    ],
+                    "input_layers": [["input_layer", 0, 0]],
+                    "output_layers": ["dense", 0, 0]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let dense = &config.get_layers()[1];
+        assert_eq!(dense.get_name(), Some("dense"));
+        assert_eq!(
+            dense.get_inbound_nodes(),
+            vec![vec![TensorReference::new(
+                String::from("input_layer"),
+                0,
+                0
+            )]]
+        );
+        assert_eq!(
+            config.get_input_layers(),
+            vec![TensorReference::new(String::from("input_layer"), 0, 0)]
+        );
+        assert_eq!(
+            config.get_output_layers(),
+            vec![TensorReference::new(String::from("dense"), 0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_legacy_call_keywords() {
+        let layer: Layer = serde_json::from_str(
+            r#"{
+                "class_name": "MultiHeadAttention",
+                "config": {"name": "attention"},
+                "name": "attention",
+                "inbound_nodes": [[["query", 0, 0, {"value": ["value", 0, 0], "use_causal_mask": true}]]]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            layer.get_inbound_nodes(),
+            vec![vec![
+                TensorReference::new(String::from("query"), 0, 0),
+                TensorReference::new(String::from("value"), 0, 0),
+            ]]
+        );
+        assert_eq!(
+            layer.get_call_keywords()[0]["use_causal_mask"],
+            Value::Bool(true)
+        );
+    }
+
+    #[test]
+    fn test_legacy_inbound_nodes() {
+        let layer: Layer = serde_json::from_str(
+            r#"{
+                "class_name": "Dense",
+                "config": {"name": "dense_1"},
+                "name": "dense_1",
+                "inbound_nodes": [[["dense", 0, 0, {}]], [["input_2", 0, 0, {}]]]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            layer.get_inbound_nodes(),
+            vec![
+                vec![TensorReference::new(String::from("dense"), 0, 0)],
+                vec![TensorReference::new(String::from("input_2"), 0, 0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metadata_deserialization() {
+        let metadata: Metadata = serde_json::from_str(
+            r#"{"keras_version": "3.1.1", "date_saved": "2024-03-30@18:20:11"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.get_keras_version(), "3.1.1");
+        assert_eq!(metadata.get_date_saved(), Some("2024-03-30@18:20:11"));
+    }
 }
@@ -0,0 +1,466 @@
+//! Typed `config` of every layer type, parsed from the loose JSON object Keras stores.
+
+use crate::layer::{ActivationFunction, DataFormat, MergeMode, Padding};
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::hash_map;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// The `config` of a layer doesn't match what its type expects.
+#[derive(Debug, Error)]
+#[error("Invalid config of layer {layer} ({class_name}): {error}")]
+pub struct LayerConfigError {
+    layer: String,
+    class_name: String,
+    error: serde_json::Error,
+}
+
+impl LayerConfigError {
+    pub fn new(layer: String, class_name: String, error: serde_json::Error) -> Self {
+        Self {
+            layer,
+            class_name,
+            error,
+        }
+    }
+
+    pub fn get_layer(&self) -> &str {
+        &self.layer
+    }
+}
+
+/// Fields Keras writes for training or for its own bookkeeping, which inference ignores.
+const UNUSED_FIELDS: [&str; 29] = [
+    "name",
+    "trainable",
+    "dtype",
+    "batch_input_shape",
+    "batch_shape",
+    "input_dim",
+    "input_length",
+    "sparse",
+    "ragged",
+    "dropout",
+    "recurrent_dropout",
+    "rate",
+    "noise_shape",
+    "seed",
+    "stateful",
+    "unroll",
+    "implementation",
+    "time_major",
+    "unit_forget_bias",
+    "momentum",
+    "renorm",
+    "renorm_clipping",
+    "renorm_momentum",
+    "fused",
+    "virtual_batch_size",
+    "adjustment",
+    "synchronized",
+    "lora_rank",
+    "quantization_config",
+];
+
+fn is_unused_field(field: &str) -> bool {
+    UNUSED_FIELDS.contains(&field)
+        || ["_initializer", "_regularizer", "_constraint"]
+            .iter()
+            .any(|suffix| field.ends_with(suffix))
+        || field.starts_with("lora_")
+}
+
+/// Deserializes a config struct from the `config` of a layer, logging the fields the struct
+/// doesn't know and prefixing errors with the field that failed.
+pub(crate) struct ConfigDeserializer<'de> {
+    layer: &'de str,
+    config: &'de HashMap<String, Value>,
+}
+
+impl<'de> ConfigDeserializer<'de> {
+    pub(crate) fn new(layer: &'de str, config: &'de HashMap<String, Value>) -> Self {
+        Self { layer, config }
+    }
+}
+
+impl<'de> Deserializer<'de> for ConfigDeserializer<'de> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(ConfigFields {
+            entries: self.config.iter(),
+            value: None,
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        for field in self.config.keys() {
+            if !fields.contains(&field.as_str()) && !is_unused_field(field) {
+                log::warn!(
+                    "Layer {} ignores the unknown config field {field}",
+                    self.layer
+                );
+            }
+        }
+        self.deserialize_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+        ignored_any
+    }
+}
+
+struct ConfigFields<'de> {
+    entries: hash_map::Iter<'de, String, Value>,
+    value: Option<(&'de str, &'de Value)>,
+}
+
+impl<'de> MapAccess<'de> for ConfigFields<'de> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((field, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some((field, value));
+        seed.deserialize(BorrowedStrDeserializer::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let (field, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before its field"))?;
+        seed.deserialize(value)
+            .map_err(|error| de::Error::custom(format_args!("field `{field}`: {error}")))
+    }
+}
+
+/// An `axis` given either as a single axis or as a list of them.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum Axes {
+    One(isize),
+    Many(Vec<isize>),
+}
+
+impl Axes {
+    pub fn to_vec(&self) -> Vec<isize> {
+        match self {
+            Axes::One(axis) => vec![*axis],
+            Axes::Many(axes) => axes.clone(),
+        }
+    }
+
+    /// The axis when there is exactly one.
+    pub fn single(&self) -> Option<isize> {
+        match self.to_vec().as_slice() {
+            [axis] => Some(*axis),
+            _ => None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActivationConfig {
+    pub activation: ActivationFunction,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReluConfig {
+    pub max_value: Option<f32>,
+    #[serde(default)]
+    pub negative_slope: f32,
+    #[serde(default)]
+    pub threshold: f32,
+}
+
+/// Keras 2 calls the slope `alpha`, Keras 3 `negative_slope`.
+#[derive(Debug, Deserialize)]
+pub struct LeakyReluConfig {
+    pub negative_slope: Option<f32>,
+    pub alpha: Option<f32>,
+}
+
+impl LeakyReluConfig {
+    pub fn get_negative_slope(&self) -> f32 {
+        self.negative_slope.or(self.alpha).unwrap_or(0.3)
+    }
+}
+
+/// `alpha` is read from the weights, which the shared axes are already folded into.
+#[derive(Debug, Deserialize)]
+pub struct PReluConfig {
+    pub shared_axes: Option<Vec<isize>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EluConfig {
+    pub alpha: Option<f32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SoftmaxConfig {
+    pub axis: Axes,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConcatenateConfig {
+    pub axis: isize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DotConfig {
+    pub axes: Axes,
+    #[serde(default)]
+    pub normalize: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DenseConfig {
+    pub units: usize,
+    pub activation: Option<ActivationFunction>,
+    #[serde(default = "default_true")]
+    pub use_bias: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Conv2DConfig {
+    pub filters: usize,
+    pub kernel_size: [usize; 2],
+    pub strides: [usize; 2],
+    pub padding: Padding,
+    pub dilation_rate: [usize; 2],
+    pub data_format: DataFormat,
+    pub groups: Option<usize>,
+    pub activation: Option<ActivationFunction>,
+    #[serde(default = "default_true")]
+    pub use_bias: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingConfig {
+    pub output_dim: usize,
+    #[serde(default)]
+    pub mask_zero: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FlattenConfig {
+    pub data_format: Option<DataFormat>,
+}
+
+/// Config of `MaxPooling1D`, `AveragePooling2D` and the like. The strides default to the
+/// pool size.
+#[derive(Debug, Deserialize)]
+pub struct PoolingConfig {
+    pub pool_size: Vec<usize>,
+    pub strides: Option<Vec<usize>>,
+    pub padding: Padding,
+    pub data_format: DataFormat,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GlobalPoolingConfig {
+    pub data_format: DataFormat,
+    #[serde(default)]
+    pub keepdims: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchNormalizationConfig {
+    pub axis: Axes,
+    pub epsilon: f32,
+    pub center: bool,
+    pub scale: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LayerNormalizationConfig {
+    pub axis: Axes,
+    pub epsilon: f32,
+    pub center: bool,
+    pub scale: bool,
+    #[serde(default)]
+    pub rms_scaling: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GroupNormalizationConfig {
+    pub groups: usize,
+    pub axis: isize,
+    pub epsilon: f32,
+    pub center: bool,
+    pub scale: bool,
+}
+
+/// Config of `LSTM`, `GRU` and `SimpleRNN`. Only the first two have a recurrent activation
+/// and only `GRU` has `reset_after`.
+#[derive(Debug, Deserialize)]
+pub struct RecurrentConfig {
+    pub units: usize,
+    pub activation: ActivationFunction,
+    pub recurrent_activation: Option<ActivationFunction>,
+    #[serde(default = "default_true")]
+    pub use_bias: bool,
+    #[serde(default)]
+    pub return_sequences: bool,
+    #[serde(default)]
+    pub return_state: bool,
+    #[serde(default)]
+    pub go_backwards: bool,
+    #[serde(default)]
+    pub zero_output_for_mask: bool,
+    pub reset_after: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BidirectionalConfig {
+    pub layer: Value,
+    pub backward_layer: Option<Value>,
+    pub merge_mode: Option<MergeMode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TimeDistributedConfig {
+    pub layer: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MultiHeadAttentionConfig {
+    pub num_heads: usize,
+    pub key_dim: usize,
+    pub value_dim: Option<usize>,
+    #[serde(default = "default_true")]
+    pub use_bias: bool,
+    pub output_shape: Option<Value>,
+    pub attention_axes: Option<Axes>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReshapeConfig {
+    pub target_shape: Vec<isize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PermuteConfig {
+    pub dims: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepeatVectorConfig {
+    pub n: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Collects the warnings logged by the tests.
+    struct Warnings(Mutex<Vec<String>>);
+
+    static WARNINGS: Warnings = Warnings(Mutex::new(Vec::new()));
+
+    impl log::Log for Warnings {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn parse<T: for<'de> Deserialize<'de>>(config: Value) -> Result<T, serde_json::Error> {
+        let config: HashMap<String, Value> = serde_json::from_value(config).unwrap();
+        T::deserialize(ConfigDeserializer::new("layer", &config))
+    }
+
+    #[test]
+    fn dense_config() {
+        let config: DenseConfig = parse(serde_json::json!({
+            "name": "dense",
+            "units": 3,
+            "activation": "relu",
+            "kernel_initializer": {"class_name": "GlorotUniform"},
+        }))
+        .unwrap();
+
+        assert_eq!(config.units, 3);
+        assert_eq!(config.activation, Some(ActivationFunction::ReLu));
+        assert!(config.use_bias);
+    }
+
+    #[test]
+    fn unknown_field_is_ignored_with_a_warning() {
+        let _ = log::set_logger(&WARNINGS);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let config: DenseConfig = parse(serde_json::json!({
+            "name": "dense",
+            "units": 3,
+            "trainable": true,
+            "frobnicate": 1,
+        }))
+        .unwrap();
+
+        assert_eq!(config.units, 3);
+        let warnings = WARNINGS.0.lock().unwrap();
+        assert!(warnings.contains(&String::from(
+            "Layer layer ignores the unknown config field frobnicate"
+        )));
+        assert!(!warnings
+            .iter()
+            .any(|warning| warning.ends_with("trainable")));
+    }
+
+    #[test]
+    fn error_names_the_field() {
+        let error = parse::<DenseConfig>(serde_json::json!({"units": "three"})).unwrap_err();
+
+        assert!(error.to_string().starts_with("field `units`: invalid type"));
+    }
+
+    #[test]
+    fn error_names_missing_field() {
+        let error = parse::<ReshapeConfig>(serde_json::json!({"name": "reshape"})).unwrap_err();
+
+        assert!(error.to_string().contains("missing field `target_shape`"));
+    }
+
+    #[test]
+    fn axes() {
+        let single: SoftmaxConfig = parse(serde_json::json!({"axis": -1})).unwrap();
+        let list: SoftmaxConfig = parse(serde_json::json!({"axis": [1, 2]})).unwrap();
+
+        assert_eq!(single.axis.single(), Some(-1));
+        assert_eq!(list.axis.to_vec(), vec![1, 2]);
+        assert_eq!(list.axis.single(), None);
+    }
+}
@@ -1,29 +1,170 @@
 use crate::NArray;
 use crate::{
-    activations::{relu, sigmoid, softmax},
+    activations::{
+        elu, exponential, gelu, gelu_approximate, get_registered_activation, hard_sigmoid,
+        hard_silu, leaky_relu, log_softmax, mish, relu, relu6, selu, sigmoid, silu, softmax,
+        softplus, softsign, tanh, ActivationFn,
+    },
     layer::{Layer, NdResult},
 };
 use serde::Deserialize;
+use std::cmp::Ordering;
+use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "ActivationConfig")]
 pub enum ActivationFunction {
-    #[serde(alias = "sigmoid")]
     Sigmoid,
-    #[serde(alias = "relu")]
     ReLu,
-    #[serde(alias = "softmax")]
     SoftMax,
-    #[serde(alias = "linear")]
     Linear,
+    Tanh,
+    Elu,
+    Selu,
+    Gelu,
+    /// `gelu(x, approximate=True)`, which Keras doesn't serialize by name.
+    GeluApproximate,
+    Silu,
+    SoftPlus,
+    SoftSign,
+    HardSigmoid,
+    HardSilu,
+    Exponential,
+    ReLu6,
+    /// Keras' default `negative_slope` of 0.2.
+    LeakyReLu,
+    Mish,
+    LogSoftMax,
+    /// Function registered with [`crate::activations::register_activation`].
+    Custom(CustomActivation),
+}
+
+/// Named activation function provided by the user.
+#[derive(Clone)]
+pub struct CustomActivation {
+    name: String,
+    function: ActivationFn,
+}
+
+impl CustomActivation {
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Debug for CustomActivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CustomActivation").field(&self.name).finish()
+    }
+}
+
+// Functions can't be compared, two custom activations are the same when their names are.
+impl PartialEq for CustomActivation {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CustomActivation {}
+
+impl PartialOrd for CustomActivation {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CustomActivation {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name.cmp(&other.name)
+    }
+}
+
+/// An activation as Keras serializes it: either its name, or with Keras 3 an object such as
+/// `{"module": "keras.activations", "class_name": "function", "config": "relu"}`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ActivationConfig {
+    Name(String),
+    Object {
+        config: String,
+        registered_name: Option<String>,
+    },
+}
+
+impl TryFrom<ActivationConfig> for ActivationFunction {
+    type Error = String;
+
+    fn try_from(config: ActivationConfig) -> Result<Self, Self::Error> {
+        let names = match config {
+            ActivationConfig::Name(name) => vec![name],
+            // Custom functions are registered in Keras as `package>name`.
+            ActivationConfig::Object {
+                config,
+                registered_name,
+            } => registered_name.into_iter().chain([config]).collect(),
+        };
+        names
+            .iter()
+            .find_map(|name| Self::from_name(name))
+            .ok_or_else(|| format!("unknown activation function {}", names.join(" / ")))
+    }
 }
 
 impl ActivationFunction {
+    /// Looks up an activation by its Keras name. Registered functions take precedence over
+    /// the built-in ones, like Keras' custom objects do.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if let Some(function) = get_registered_activation(name) {
+            return Some(Self::Custom(CustomActivation {
+                name: name.to_owned(),
+                function,
+            }));
+        }
+        Some(match name {
+            "sigmoid" => Self::Sigmoid,
+            "relu" => Self::ReLu,
+            "softmax" => Self::SoftMax,
+            "linear" => Self::Linear,
+            "tanh" => Self::Tanh,
+            "elu" => Self::Elu,
+            "selu" => Self::Selu,
+            "gelu" => Self::Gelu,
+            "silu" | "swish" => Self::Silu,
+            "softplus" => Self::SoftPlus,
+            "softsign" => Self::SoftSign,
+            "hard_sigmoid" => Self::HardSigmoid,
+            "hard_silu" | "hard_swish" => Self::HardSilu,
+            "exponential" => Self::Exponential,
+            "relu6" => Self::ReLu6,
+            "leaky_relu" => Self::LeakyReLu,
+            "mish" => Self::Mish,
+            "log_softmax" => Self::LogSoftMax,
+            _ => return None,
+        })
+    }
+
     pub fn compute(&self, incoming: NArray) -> NArray {
         match self {
             Self::ReLu => relu(incoming),
             Self::Sigmoid => sigmoid(incoming),
             Self::SoftMax => softmax(incoming),
             Self::Linear => incoming,
+            Self::Tanh => tanh(incoming),
+            Self::Elu => elu(incoming, 1.0),
+            Self::Selu => selu(incoming),
+            Self::Gelu => gelu(incoming),
+            Self::GeluApproximate => gelu_approximate(incoming),
+            Self::Silu => silu(incoming),
+            Self::SoftPlus => softplus(incoming),
+            Self::SoftSign => softsign(incoming),
+            Self::HardSigmoid => hard_sigmoid(incoming),
+            Self::HardSilu => hard_silu(incoming),
+            Self::Exponential => exponential(incoming),
+            Self::ReLu6 => relu6(incoming),
+            Self::LeakyReLu => leaky_relu(incoming, 0.2),
+            Self::Mish => mish(incoming),
+            Self::LogSoftMax => log_softmax(incoming),
+            Self::Custom(custom) => (custom.function)(incoming),
         }
     }
 }
@@ -106,4 +247,52 @@ mod tests {
             assert_approx_eq!(actual, expected, 1e-6);
         }
     }
+
+    #[test]
+    fn activation_names() {
+        let parse = |name: &str| -> ActivationFunction {
+            serde_json::from_value(serde_json::Value::from(name)).unwrap()
+        };
+
+        assert_eq!(parse("swish"), ActivationFunction::Silu);
+        assert_eq!(parse("silu"), ActivationFunction::Silu);
+        assert_eq!(parse("hard_swish"), ActivationFunction::HardSilu);
+        assert_eq!(parse("relu6"), ActivationFunction::ReLu6);
+        assert_eq!(parse("leaky_relu"), ActivationFunction::LeakyReLu);
+        assert_eq!(parse("log_softmax"), ActivationFunction::LogSoftMax);
+        assert_eq!(parse("gelu"), ActivationFunction::Gelu);
+    }
+
+    #[test]
+    fn activation_object_config() {
+        let activation: ActivationFunction = serde_json::from_str(
+            r#"{"module": "keras.activations", "class_name": "function", "config": "relu", "registered_name": null}"#,
+        )
+        .unwrap();
+
+        assert_eq!(activation, ActivationFunction::ReLu);
+    }
+
+    #[test]
+    fn unknown_activation() {
+        let error = serde_json::from_str::<ActivationFunction>(r#""not_an_activation""#)
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("not_an_activation"));
+    }
+
+    #[test]
+    fn custom_activation() {
+        crate::activations::register_activation("my_package>double", |z| z * 2.0);
+        let activation: ActivationFunction = serde_json::from_str(
+            r#"{"module": "my_package", "class_name": "function", "config": "double", "registered_name": "my_package>double"}"#,
+        )
+        .unwrap();
+
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[2]), vec![1.0, -3.0]).unwrap();
+        let output = Activation::new(activation).compute(input).unwrap();
+
+        assert_eq!(output.as_slice().unwrap(), &[2.0, -6.0]);
+    }
 }
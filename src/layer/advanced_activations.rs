@@ -0,0 +1,229 @@
+use crate::activations::{elu, softmax_axis};
+use crate::layer::{normalize_axis, Layer, LayerError, NdResult, ParamCount};
+use crate::NArray;
+
+/// Keras `ReLU` layer: `max_value` caps the output and values below `threshold` are scaled
+/// by `negative_slope` after subtracting the threshold.
+pub struct Relu {
+    max_value: Option<f32>,
+    negative_slope: f32,
+    threshold: f32,
+}
+
+impl Relu {
+    pub fn new(max_value: Option<f32>, negative_slope: f32, threshold: f32) -> Self {
+        Self {
+            max_value,
+            negative_slope,
+            threshold,
+        }
+    }
+}
+
+impl Layer for Relu {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(incoming.mapv(|x| match self.max_value {
+            Some(max_value) if x >= max_value => max_value,
+            _ if x >= self.threshold => x,
+            _ => self.negative_slope * (x - self.threshold),
+        }))
+    }
+}
+
+/// Keras `LeakyReLU` layer, called `alpha` by Keras 2 and `negative_slope` by Keras 3.
+pub struct LeakyRelu {
+    negative_slope: f32,
+}
+
+impl LeakyRelu {
+    pub fn new(negative_slope: f32) -> Self {
+        Self { negative_slope }
+    }
+}
+
+impl Layer for LeakyRelu {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(incoming.mapv(|x| if x >= 0.0 { x } else { self.negative_slope * x }))
+    }
+}
+
+/// Keras `PReLU` layer with its learned `alpha`, shaped like a sample. The `shared_axes`
+/// have a size of one in it, so it is broadcast over them.
+pub struct PRelu {
+    alpha: NArray,
+}
+
+impl PRelu {
+    pub fn new(alpha: NArray) -> Self {
+        Self { alpha }
+    }
+}
+
+impl Layer for PRelu {
+    fn param_count(&self) -> ParamCount {
+        ParamCount::trainable(self.alpha.len())
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let sample_shape = &input_shape[1.min(input_shape.len())..];
+        // Same rule as `broadcast` in `compute`, an unknown size may be anything.
+        let broadcasts = self.alpha.ndim() <= sample_shape.len()
+            && self
+                .alpha
+                .shape()
+                .iter()
+                .rev()
+                .zip(sample_shape.iter().rev())
+                .all(|(&alpha, size)| alpha == 1 || size.is_none_or(|size| size == alpha));
+        if broadcasts {
+            Ok(input_shape.to_vec())
+        } else {
+            Err(LayerError::incompatible_shape(
+                "PReLU",
+                input_shape,
+                format!("alpha of shape {:?} doesn't match it", self.alpha.shape()),
+            ))
+        }
+    }
+
+    fn compute(&self, mut incoming: NArray) -> NdResult {
+        let sample_shape = &incoming.shape()[1.min(incoming.ndim())..];
+        let Some(alpha) = self.alpha.broadcast(sample_shape) else {
+            return Err(LayerError::incompatible_input(
+                "PReLU",
+                incoming.shape(),
+                format!("alpha of shape {:?} doesn't match it", self.alpha.shape()),
+            ));
+        };
+        for mut sample in incoming.outer_iter_mut() {
+            sample.zip_mut_with(&alpha, |x, &alpha| {
+                if *x < 0.0 {
+                    *x *= alpha;
+                }
+            });
+        }
+        Ok(incoming)
+    }
+}
+
+/// Keras `ELU` layer.
+pub struct Elu {
+    alpha: f32,
+}
+
+impl Elu {
+    pub fn new(alpha: f32) -> Self {
+        Self { alpha }
+    }
+}
+
+impl Layer for Elu {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(elu(incoming, self.alpha))
+    }
+}
+
+/// Keras `Softmax` layer, normalizing along `axis`.
+pub struct Softmax {
+    axis: isize,
+}
+
+impl Softmax {
+    pub fn new(axis: isize) -> Self {
+        Self { axis }
+    }
+}
+
+impl Layer for Softmax {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let axis = normalize_axis(self.axis, incoming.ndim())?;
+        Ok(softmax_axis(incoming, axis))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn input() -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(&[1, 4]), vec![-2.0, 0.5, 3.0, 8.0]).unwrap()
+    }
+
+    #[test]
+    fn relu_parameters() {
+        let output = Relu::new(Some(6.0), 0.1, 1.0).compute(input()).unwrap();
+
+        let expected = [-0.3, -0.05, 3.0, 6.0];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn leaky_relu() {
+        let output = LeakyRelu::new(0.3).compute(input()).unwrap();
+
+        assert_approx_eq!(output[[0, 0]], -0.6, 1e-6);
+        assert_approx_eq!(output[[0, 3]], 8.0, 1e-6);
+    }
+
+    #[test]
+    fn prelu_shared_axes() {
+        // alpha of a [batch, 2, 2] input with shared_axes=[1].
+        let alpha = NArray::from_shape_vec(ndarray::IxDyn(&[1, 2]), vec![0.5, 2.0]).unwrap();
+        let input = input().into_shape(vec![1, 2, 2]).unwrap();
+
+        let output = PRelu::new(alpha).compute(input).unwrap();
+
+        assert_eq!(output.as_slice().unwrap(), &[-1.0, 0.5, 3.0, 8.0]);
+    }
+
+    #[test]
+    fn prelu_output_shape() {
+        let alpha = NArray::from_shape_vec(ndarray::IxDyn(&[1, 2]), vec![0.5, 2.0]).unwrap();
+        let layer = PRelu::new(alpha);
+
+        let output_shape = layer.output_shape(&[None, None, Some(2)]).unwrap();
+        let error = layer.output_shape(&[None, Some(2), Some(3)]).unwrap_err();
+
+        assert_eq!(output_shape, vec![None, None, Some(2)]);
+        assert_eq!(
+            error.to_string(),
+            "PReLU can't take an input of shape (None, 2, 3): alpha of shape [1, 2] doesn't match it"
+        );
+    }
+
+    #[test]
+    fn elu_alpha() {
+        let output = Elu::new(0.5).compute(input()).unwrap();
+
+        assert_approx_eq!(output[[0, 0]], -0.4323324, 1e-6);
+        assert_approx_eq!(output[[0, 1]], 0.5, 1e-6);
+    }
+
+    #[test]
+    fn softmax_first_axis() {
+        let input = input().into_shape(vec![2, 2]).unwrap();
+
+        let output = Softmax::new(0).compute(input).unwrap();
+
+        assert_approx_eq!(output[[0, 0]] + output[[1, 0]], 1.0, 1e-6);
+        assert_approx_eq!(output[[0, 0]], 0.00669285, 1e-6);
+        assert_approx_eq!(output[[1, 1]], 0.9994472, 1e-6);
+    }
+
+    #[test]
+    fn softmax_negative_axis() {
+        let input = input().into_shape(vec![2, 2]).unwrap();
+
+        let output = Softmax::new(-2).compute(input).unwrap();
+
+        assert_approx_eq!(output[[0, 0]] + output[[1, 0]], 1.0, 1e-6);
+        assert_approx_eq!(output[[0, 0]], 0.00669285, 1e-6);
+        assert_approx_eq!(output[[1, 1]], 0.9994472, 1e-6);
+    }
+}
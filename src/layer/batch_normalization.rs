@@ -0,0 +1,183 @@
+use crate::layer::{expect_size, normalize_axis, Layer, LayerError, NdResult, ParamCount};
+use crate::{NArray, Vector};
+use ndarray::{ErrorKind, ShapeError};
+
+/// Keras `BatchNormalization` layer at inference, normalizing `axis` with the moving
+/// statistics. They are combined with `gamma` and `beta` into a single scale and offset.
+pub struct BatchNormalization {
+    axis: isize,
+    input_rank: Option<usize>,
+    scale: Vector,
+    offset: Vector,
+    scaled: bool,
+    centered: bool,
+}
+
+impl BatchNormalization {
+    /// `gamma` and `beta` are missing when Keras was told not to `scale` or `center`.
+    /// `input_rank` lets a positive `axis` be matched against the preceding layer's output.
+    pub fn new(
+        axis: isize,
+        input_rank: Option<usize>,
+        gamma: Option<Vector>,
+        beta: Option<Vector>,
+        moving_mean: Vector,
+        moving_variance: Vector,
+        epsilon: f32,
+    ) -> Self {
+        let (scaled, centered) = (gamma.is_some(), beta.is_some());
+        let channels = moving_mean.len();
+        let gamma = gamma.unwrap_or_else(|| Vector::ones(channels));
+        let beta = beta.unwrap_or_else(|| Vector::zeros(channels));
+        let scale = gamma / moving_variance.mapv(|variance| (variance + epsilon).sqrt());
+        let offset = beta - moving_mean * &scale;
+        Self {
+            axis,
+            input_rank,
+            scale,
+            offset,
+            scaled,
+            centered,
+        }
+    }
+
+    pub fn get_scale(&self) -> &Vector {
+        &self.scale
+    }
+
+    pub fn get_offset(&self) -> &Vector {
+        &self.offset
+    }
+
+    /// Whether the layer normalizes `axis` of an input of rank `rank`, when known.
+    pub fn normalizes(&self, axis: isize, rank: Option<usize>) -> bool {
+        let normalize = |axis: isize| match self.input_rank.or(rank) {
+            Some(rank) if axis < 0 => axis + rank as isize,
+            _ => axis,
+        };
+        normalize(self.axis) == normalize(axis)
+    }
+}
+
+impl Layer for BatchNormalization {
+    fn param_count(&self) -> ParamCount {
+        // gamma and beta are trained, the moving mean and variance aren't.
+        let channels = self.scale.len();
+        ParamCount {
+            trainable: (usize::from(self.scaled) + usize::from(self.centered)) * channels,
+            non_trainable: 2 * channels,
+        }
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let axis = normalize_axis(self.axis, incoming.ndim())?;
+        if incoming.shape()[axis] != self.scale.len() {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        let mut shape = vec![1; incoming.ndim()];
+        shape[axis] = self.scale.len();
+        let scale = self.scale.view().into_shape(shape.clone())?;
+        let offset = self.offset.view().into_shape(shape)?;
+        Ok(incoming * scale + offset)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let axis = normalize_axis(self.axis, input_shape.len()).map_err(|_| {
+            LayerError::incompatible_shape(
+                "BatchNormalization",
+                input_shape,
+                format!("the input has no axis {}", self.axis),
+            )
+        })?;
+        expect_size("BatchNormalization", input_shape, axis, self.scale.len())?;
+        Ok(input_shape.to_vec())
+    }
+
+    fn batch_normalization(&self) -> Option<&BatchNormalization> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn batch_normalization(axis: isize) -> BatchNormalization {
+        BatchNormalization::new(
+            axis,
+            None,
+            Some(Vector::from_vec(vec![2.0, 1.0])),
+            Some(Vector::from_vec(vec![0.5, 0.0])),
+            Vector::from_vec(vec![1.0, -1.0]),
+            Vector::from_vec(vec![4.0, 0.25]),
+            0.0,
+        )
+    }
+
+    #[test]
+    fn batch_normalization_last_axis() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![3.0, 0.0, 1.0, -1.5]).unwrap();
+
+        let output = batch_normalization(-1).compute(input).unwrap();
+
+        let expected = [2.5, 2.0, 0.5, -1.0];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn batch_normalization_channels_first() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[1, 2, 2]), vec![3.0, 1.0, 0.0, -1.5]).unwrap();
+
+        let output = batch_normalization(1).compute(input).unwrap();
+
+        let expected = [2.5, 0.5, 2.0, -1.0];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn batch_normalization_normalizes_axis() {
+        assert!(batch_normalization(-1).normalizes(3, Some(4)));
+        assert!(batch_normalization(1).normalizes(-3, Some(4)));
+        assert!(!batch_normalization(1).normalizes(-1, Some(4)));
+        assert!(!batch_normalization(1).normalizes(-1, None));
+    }
+
+    #[test]
+    fn batch_normalization_param_count() {
+        let moving = || Vector::from_vec(vec![0.0, 1.0]);
+        let without_scale = BatchNormalization::new(
+            -1,
+            None,
+            None,
+            Some(Vector::zeros(2)),
+            moving(),
+            moving(),
+            0.001,
+        );
+
+        assert_eq!(
+            batch_normalization(-1).param_count(),
+            ParamCount {
+                trainable: 4,
+                non_trainable: 4,
+            }
+        );
+        assert_eq!(
+            without_scale.param_count(),
+            ParamCount {
+                trainable: 2,
+                non_trainable: 4,
+            }
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use crate::layer::{Layer, LayerError, Mask, NdResult, ParamCount};
+use crate::NArray;
+use ndarray::Axis;
+use serde::Deserialize;
+
+/// How `Bidirectional` combines the outputs of both directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMode {
+    Concat,
+    Sum,
+    Mul,
+    Ave,
+}
+
+impl MergeMode {
+    fn merge(&self, forward: NArray, backward: NArray) -> NdResult {
+        match self {
+            MergeMode::Concat => {
+                let axis = Axis(forward.ndim() - 1);
+                Ok(ndarray::concatenate(
+                    axis,
+                    &[forward.view(), backward.view()],
+                )?)
+            }
+            MergeMode::Sum => Ok(forward + backward),
+            MergeMode::Mul => Ok(forward * backward),
+            MergeMode::Ave => Ok((forward + backward) / 2.0),
+        }
+    }
+}
+
+/// Keras `Bidirectional` wrapper running one recurrent layer forwards and another backwards.
+///
+/// Without a merge mode both outputs are returned separately. With `return_state` the states
+/// of the forward layer follow the outputs, then the ones of the backward layer.
+pub struct Bidirectional {
+    forward: Box<dyn Layer>,
+    backward: Box<dyn Layer>,
+    merge_mode: Option<MergeMode>,
+    return_sequences: bool,
+}
+
+impl Bidirectional {
+    pub fn new(
+        forward: Box<dyn Layer>,
+        backward: Box<dyn Layer>,
+        merge_mode: Option<MergeMode>,
+        return_sequences: bool,
+    ) -> Self {
+        Self {
+            forward,
+            backward,
+            merge_mode,
+            return_sequences,
+        }
+    }
+}
+
+impl Layer for Bidirectional {
+    fn param_count(&self) -> ParamCount {
+        self.forward.param_count() + self.backward.param_count()
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(self.compute_all(incoming)?.swap_remove(0))
+    }
+
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        self.compute_all_masked(incoming, None)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let mut output_shape = self.forward.output_shape(input_shape)?;
+        self.backward.output_shape(input_shape)?;
+        if self.merge_mode == Some(MergeMode::Concat) {
+            if let Some(last) = output_shape.last_mut() {
+                *last = last.map(|size| 2 * size);
+            }
+        }
+        Ok(output_shape)
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, mask: Option<&Mask>) -> Option<Mask> {
+        mask.filter(|_| self.return_sequences).cloned()
+    }
+
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        let mut forward = self.forward.compute_all_masked(incoming.clone(), mask)?;
+        let mut backward = self.backward.compute_all_masked(incoming, mask)?;
+        let forward_output = forward.remove(0);
+        let mut backward_output = backward.remove(0);
+        if self.return_sequences {
+            // The backward sequence comes in processing order, align it with the forward one.
+            backward_output.invert_axis(Axis(backward_output.ndim() - 2));
+        }
+
+        let mut outputs = match &self.merge_mode {
+            Some(merge_mode) => vec![merge_mode.merge(forward_output, backward_output)?],
+            None => vec![forward_output, backward_output],
+        };
+        outputs.extend(forward);
+        outputs.extend(backward);
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::{ActivationFunction, RecurrentOptions, SimpleRnn};
+    use crate::{Matrix, Vector};
+
+    fn cumulative_sum(go_backwards: bool) -> Box<dyn Layer> {
+        cumulative_sum_with(RecurrentOptions {
+            return_sequences: true,
+            return_state: true,
+            go_backwards,
+            zero_output_for_mask: false,
+        })
+    }
+
+    fn cumulative_sum_with(options: RecurrentOptions) -> Box<dyn Layer> {
+        Box::new(SimpleRnn::new(
+            Matrix::ones((1, 1)),
+            Matrix::ones((1, 1)),
+            Some(Vector::zeros(1)),
+            ActivationFunction::Linear,
+            options,
+        ))
+    }
+
+    fn sequence() -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(&[3, 1]), vec![1.0, 2.0, 3.0]).unwrap()
+    }
+
+    #[test]
+    fn bidirectional_concat() {
+        let layer = Bidirectional::new(
+            cumulative_sum(false),
+            cumulative_sum(true),
+            Some(MergeMode::Concat),
+            true,
+        );
+
+        let outputs = layer.compute_all(sequence()).unwrap();
+
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0].shape(), &[3, 2]);
+        assert_eq!(
+            outputs[0].iter().copied().collect::<Vec<_>>(),
+            vec![1.0, 6.0, 3.0, 5.0, 6.0, 3.0]
+        );
+        assert_eq!(outputs[1].as_slice().unwrap(), &[6.0]);
+        assert_eq!(outputs[2].as_slice().unwrap(), &[6.0]);
+    }
+
+    #[test]
+    fn bidirectional_without_merge_mode() {
+        let layer = Bidirectional::new(cumulative_sum(false), cumulative_sum(true), None, true);
+
+        let outputs = layer.compute_all(sequence()).unwrap();
+
+        assert_eq!(outputs.len(), 4);
+        assert_eq!(
+            outputs[1].iter().copied().collect::<Vec<_>>(),
+            vec![6.0, 5.0, 3.0]
+        );
+    }
+
+    #[test]
+    fn bidirectional_masked_return_sequences() {
+        let options = |go_backwards| RecurrentOptions {
+            return_sequences: true,
+            return_state: true,
+            go_backwards,
+            zero_output_for_mask: true,
+        };
+        let layer = Bidirectional::new(
+            cumulative_sum_with(options(false)),
+            cumulative_sum_with(options(true)),
+            Some(MergeMode::Concat),
+            true,
+        );
+        let mask = Mask::from_shape_vec(ndarray::IxDyn(&[3]), vec![true, false, true]).unwrap();
+
+        let outputs = layer.compute_all_masked(sequence(), Some(&mask)).unwrap();
+
+        // Keras outputs zeros on the masked timestep and carries the states over it.
+        assert_eq!(
+            outputs[0].iter().copied().collect::<Vec<_>>(),
+            vec![1.0, 4.0, 0.0, 0.0, 4.0, 3.0]
+        );
+        assert_eq!(outputs[1].as_slice().unwrap(), &[4.0]);
+        assert_eq!(outputs[2].as_slice().unwrap(), &[4.0]);
+    }
+}
@@ -0,0 +1,280 @@
+use crate::layer::spatial::{DataFormat, Padding};
+use crate::layer::{
+    compute_batched, expect_rank, expect_size, ActivationFunction, BatchNormalization, Layer,
+    LayerError, Mask, NdResult, ParamCount,
+};
+use crate::{Matrix, NArray, Vector};
+use ndarray::{Array4, ErrorKind, ShapeError};
+
+pub struct Conv2D {
+    // (kernel_height, kernel_width, input_channels, filters), as stored by Keras.
+    kernel: Array4<f32>,
+    bias: Vector,
+    use_bias: bool,
+    strides: [usize; 2],
+    padding: Padding,
+    dilation_rate: [usize; 2],
+    data_format: DataFormat,
+    activation: Option<ActivationFunction>,
+}
+
+impl Conv2D {
+    pub fn new(
+        kernel: Array4<f32>,
+        bias: Option<Vector>,
+        strides: [usize; 2],
+        padding: Padding,
+        dilation_rate: [usize; 2],
+        data_format: DataFormat,
+        activation: Option<ActivationFunction>,
+    ) -> Self {
+        let use_bias = bias.is_some();
+        let bias = bias.unwrap_or_else(|| Vector::zeros(kernel.dim().3));
+        Self {
+            kernel: kernel.as_standard_layout().into_owned(),
+            bias,
+            use_bias,
+            strides,
+            padding,
+            dilation_rate,
+            data_format,
+            activation,
+        }
+    }
+
+    /// Convolves a `[batch, height, width, channels]` array by multiplying its patches
+    /// (im2col) with the kernel reshaped to `[kernel_height * kernel_width * channels, filters]`.
+    fn convolve(&self, incoming: NArray) -> NdResult {
+        let (kernel_height, kernel_width, channels, filters) = self.kernel.dim();
+        if incoming.ndim() != 4 || incoming.shape()[3] != channels {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        let (batch, height, width) = (
+            incoming.shape()[0],
+            incoming.shape()[1],
+            incoming.shape()[2],
+        );
+        let [stride_y, stride_x] = self.strides;
+        let [dilation_y, dilation_x] = self.dilation_rate;
+        let (output_height, pad_top) =
+            self.padding
+                .output_size(height, (kernel_height - 1) * dilation_y + 1, stride_y);
+        let (output_width, pad_left) =
+            self.padding
+                .output_size(width, (kernel_width - 1) * dilation_x + 1, stride_x);
+
+        let incoming = incoming.as_standard_layout();
+        let input = incoming
+            .as_slice()
+            .ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleLayout))?;
+        let patch_len = kernel_height * kernel_width * channels;
+        let mut patches = vec![0.0; batch * output_height * output_width * patch_len];
+        for (row, patch) in patches.chunks_exact_mut(patch_len).enumerate() {
+            let sample = row / (output_height * output_width);
+            let (output_y, output_x) = (row / output_width % output_height, row % output_width);
+            for i in 0..kernel_height {
+                let y = (output_y * stride_y + i * dilation_y) as isize - pad_top as isize;
+                if y < 0 || y >= height as isize {
+                    continue;
+                }
+                for j in 0..kernel_width {
+                    let x = (output_x * stride_x + j * dilation_x) as isize - pad_left as isize;
+                    if x < 0 || x >= width as isize {
+                        continue;
+                    }
+                    let source = ((sample * height + y as usize) * width + x as usize) * channels;
+                    let target = (i * kernel_width + j) * channels;
+                    patch[target..target + channels]
+                        .copy_from_slice(&input[source..source + channels]);
+                }
+            }
+        }
+
+        let patches = Matrix::from_shape_vec((patches.len() / patch_len, patch_len), patches)?;
+        let kernel = self.kernel.view().into_shape((patch_len, filters))?;
+        let output = patches.dot(&kernel) + &self.bias;
+        Ok(output
+            .into_shape((batch, output_height, output_width, filters))?
+            .into_dyn())
+    }
+}
+
+impl Layer for Conv2D {
+    fn param_count(&self) -> ParamCount {
+        let bias = if self.use_bias { self.bias.len() } else { 0 };
+        ParamCount::trainable(self.kernel.len() + bias)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        expect_rank(
+            "Conv2D",
+            input_shape,
+            4,
+            "a batched image with height, width and channels",
+        )?;
+        let (kernel_height, kernel_width, channels, filters) = self.kernel.dim();
+        let channels_axis = self.data_format.channels_axis(4);
+        expect_size("Conv2D", input_shape, channels_axis, channels)?;
+        let windows = [
+            (kernel_height - 1) * self.dilation_rate[0] + 1,
+            (kernel_width - 1) * self.dilation_rate[1] + 1,
+        ];
+        let mut output_shape = input_shape.to_vec();
+        for ((axis, window), stride) in self
+            .data_format
+            .spatial_axes(4)
+            .zip(windows)
+            .zip(self.strides)
+        {
+            output_shape[axis] =
+                input_shape[axis].map(|size| self.padding.output_size(size, window, stride).0);
+        }
+        output_shape[channels_axis] = Some(filters);
+        Ok(output_shape)
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        compute_batched("Conv2D", incoming, 4, |incoming| {
+            let mut output = self.convolve(self.data_format.to_channels_last(incoming))?;
+            if let Some(activation) = &self.activation {
+                output = activation.compute(output);
+            }
+            Ok(self.data_format.from_channels_last(output))
+        })
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+
+    fn fold_batch_normalization(&mut self, batch_normalization: &BatchNormalization) -> bool {
+        let linear = matches!(self.activation, None | Some(ActivationFunction::Linear));
+        let channels_axis = match self.data_format {
+            DataFormat::ChannelsLast => 3,
+            DataFormat::ChannelsFirst => 1,
+        };
+        let filters = batch_normalization.get_scale().len() == self.bias.len();
+        if !linear || !filters || !batch_normalization.normalizes(channels_axis, Some(4)) {
+            return false;
+        }
+        self.kernel *= batch_normalization.get_scale();
+        self.bias = &self.bias * batch_normalization.get_scale() + batch_normalization.get_offset();
+        self.use_bias = true;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::Axis;
+
+    fn image_3x3() -> NArray {
+        NArray::from_shape_vec(
+            ndarray::IxDyn(&[3, 3, 1]),
+            (1..=9).map(|x| x as f32).collect(),
+        )
+        .unwrap()
+    }
+
+    fn ones_2x2(strides: [usize; 2], padding: Padding, dilation_rate: [usize; 2]) -> Conv2D {
+        Conv2D::new(
+            Array4::ones((2, 2, 1, 1)),
+            Some(Vector::zeros(1)),
+            strides,
+            padding,
+            dilation_rate,
+            DataFormat::ChannelsLast,
+            None,
+        )
+    }
+
+    #[test]
+    fn conv2d_valid_padding() {
+        let output = ones_2x2([1, 1], Padding::Valid, [1, 1])
+            .compute(image_3x3())
+            .unwrap();
+
+        assert_eq!(output.shape(), &[2, 2, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[12.0, 16.0, 24.0, 28.0]);
+    }
+
+    #[test]
+    fn conv2d_same_padding() {
+        let output = ones_2x2([1, 1], Padding::Same, [1, 1])
+            .compute(image_3x3())
+            .unwrap();
+
+        assert_eq!(output.shape(), &[3, 3, 1]);
+        assert_eq!(
+            output.as_slice().unwrap(),
+            &[12.0, 16.0, 9.0, 24.0, 28.0, 15.0, 15.0, 17.0, 9.0]
+        );
+    }
+
+    #[test]
+    fn conv2d_same_padding_with_strides() {
+        let output = ones_2x2([2, 2], Padding::Same, [1, 1])
+            .compute(image_3x3().insert_axis(Axis(0)))
+            .unwrap();
+
+        assert_eq!(output.shape(), &[1, 2, 2, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[12.0, 9.0, 15.0, 9.0]);
+    }
+
+    #[test]
+    fn conv2d_dilation() {
+        let output = ones_2x2([1, 1], Padding::Valid, [2, 2])
+            .compute(image_3x3())
+            .unwrap();
+
+        assert_eq!(output.as_slice().unwrap(), &[20.0]);
+    }
+
+    #[test]
+    fn conv2d_channels_first_with_bias_and_activation() {
+        let kernel = Array4::from_shape_vec((1, 1, 2, 2), vec![1.0, 2.0, 3.0, -4.0]).unwrap();
+        let conv = Conv2D::new(
+            kernel,
+            Some(Vector::from_vec(vec![1.0, -1.0])),
+            [1, 1],
+            Padding::Valid,
+            [1, 1],
+            DataFormat::ChannelsFirst,
+            Some(ActivationFunction::ReLu),
+        );
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[2, 1, 1]), vec![1.0, 2.0]).unwrap();
+
+        let output = conv.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[2, 1, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[8.0, 0.0]);
+    }
+
+    #[test]
+    fn conv2d_output_shape() {
+        let output_shape = ones_2x2([2, 2], Padding::Same, [1, 1])
+            .output_shape(&[None, Some(3), Some(5), Some(1)])
+            .unwrap();
+
+        assert_eq!(output_shape, vec![None, Some(2), Some(3), Some(1)]);
+    }
+
+    #[test]
+    fn conv2d_wrong_rank() {
+        let input = NArray::zeros(ndarray::IxDyn(&[1, 1, 3, 3, 1]));
+
+        let error = ones_2x2([1, 1], Padding::Valid, [1, 1])
+            .compute(input)
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Conv2D can't handle an input of shape [1, 1, 3, 3, 1]: \
+             expected 4 axes, or 3 for a single sample"
+        );
+    }
+}
@@ -1,49 +1,85 @@
-use crate::layer::{ActivationFunction, Layer, NdResult};
+use crate::layer::{
+    expect_size, ActivationFunction, BatchNormalization, Layer, LayerError, NdResult, ParamCount,
+};
 use crate::{Matrix, NArray, Vector};
 
 pub struct Dense {
     weights: Matrix,
     bias: Vector,
+    use_bias: bool,
     activation: Option<ActivationFunction>,
 }
 
 impl Dense {
-    pub fn new(weights: Matrix, bias: Vector, activation: Option<ActivationFunction>) -> Self {
+    pub fn new(
+        weights: Matrix,
+        bias: Option<Vector>,
+        activation: Option<ActivationFunction>,
+    ) -> Self {
+        let use_bias = bias.is_some();
+        let bias = bias.unwrap_or_else(|| Vector::zeros(weights.ncols()));
         Self {
             weights,
             bias,
+            use_bias,
             activation,
         }
     }
-
-    pub fn from_hdf5(
-        file: &hdf5::File,
-        layer_name: &str,
-        activation: Option<ActivationFunction>,
-    ) -> Result<Self, hdf5::Error> {
-        let base_path = format!(r"/layers\{layer_name}/vars");
-        dbg!(&base_path);
-        let weights: Matrix = file
-            .dataset(format!("{}/0", base_path).as_str())?
-            .read_2d()?;
-        let bias: Vector = file
-            .dataset(format!("{}/1", base_path).as_str())?
-            .read_1d()?;
-        Ok(Self::new(weights, bias, activation))
-    }
 }
 
 impl Layer for Dense {
+    fn param_count(&self) -> ParamCount {
+        let bias = if self.use_bias { self.bias.len() } else { 0 };
+        ParamCount::trainable(self.weights.len() + bias)
+    }
+
+    /// Applies the kernel along the last axis, so `[batch, features]` becomes `[batch, units]`
+    /// and `[batch, timesteps, features]` becomes `[batch, timesteps, units]`.
     fn compute(&self, incoming: NArray) -> NdResult {
-        let incoming_len = incoming.len();
-        let arr_1d: Vector = incoming.into_shape(incoming_len)?;
-        let computation_result = arr_1d.dot(&self.weights) + self.bias.clone();
-        if let Some(activation) = &self.activation {
-            let result_1d = computation_result.into_dimensionality()?;
-            Ok(activation.compute(result_1d))
-        } else {
-            computation_result.into_dimensionality()
+        let features = self.weights.nrows();
+        if incoming.shape().last() != Some(&features) {
+            return Err(LayerError::incompatible_input(
+                "Dense",
+                incoming.shape(),
+                format!("expected {features} features on the last axis"),
+            ));
+        }
+        let mut output_shape = incoming.shape().to_vec();
+        output_shape[incoming.ndim() - 1] = self.weights.ncols();
+
+        let rows = incoming.len() / features;
+        let incoming: Matrix = incoming
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((rows, features))?;
+        let output = (incoming.dot(&self.weights) + &self.bias).into_shape(output_shape)?;
+        Ok(match &self.activation {
+            Some(activation) => activation.compute(output),
+            None => output,
+        })
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let last = input_shape.len().saturating_sub(1);
+        expect_size("Dense", input_shape, last, self.weights.nrows())?;
+        let mut output_shape = input_shape.to_vec();
+        output_shape[last] = Some(self.weights.ncols());
+        Ok(output_shape)
+    }
+
+    fn fold_batch_normalization(&mut self, batch_normalization: &BatchNormalization) -> bool {
+        let linear = matches!(self.activation, None | Some(ActivationFunction::Linear));
+        let units = batch_normalization.get_scale().len() == self.bias.len();
+        if !linear || !units || !batch_normalization.normalizes(-1, None) {
+            return false;
         }
+        self.weights *= batch_normalization.get_scale();
+        self.bias = &self.bias * batch_normalization.get_scale() + batch_normalization.get_offset();
+        self.use_bias = true;
+        true
     }
 
     fn weights(&self) -> &Matrix {
@@ -70,7 +106,7 @@ mod tests {
         let weights = Matrix::ones((3, 3));
         let bias = Vector::zeros(3);
 
-        let dense_without_activation = Dense::new(weights.clone(), bias.clone(), None);
+        let dense_without_activation = Dense::new(weights.clone(), Some(bias.clone()), None);
         assert_eq!(dense_without_activation.weights, weights);
         assert_eq!(dense_without_activation.bias, bias);
         assert_eq!(dense_without_activation.activation, None);
@@ -82,7 +118,7 @@ mod tests {
             Matrix::from_shape_vec((3, 3), vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0])
                 .unwrap();
         let bias = Vector::from_vec(vec![1.0, 2.0, 3.0]);
-        let dense_layer = Dense::new(weights, bias, None);
+        let dense_layer = Dense::new(weights, Some(bias), None);
 
         let input = NArray::from_shape_vec(ndarray::IxDyn(&[3]), vec![1.0, 2.0, 3.0]).unwrap();
 
@@ -104,7 +140,7 @@ mod tests {
         .unwrap();
         let bias = Vector::from_vec(vec![1.0, -2.0, 3.0]);
         let activation = ActivationFunction::ReLu;
-        let dense_layer = Dense::new(weights, bias, Some(activation));
+        let dense_layer = Dense::new(weights, Some(bias), Some(activation));
 
         let input = NArray::from_shape_vec(ndarray::IxDyn(&[3]), vec![-1.0, 2.0, -3.0]).unwrap();
 
@@ -116,4 +152,86 @@ mod tests {
             assert_approx_eq!(actual, expected, 1e-6);
         }
     }
+
+    #[test]
+    fn test_dense_compute_batch_along_last_axis() {
+        let weights = Matrix::from_shape_vec((2, 1), vec![1.0, -1.0]).unwrap();
+        let dense_layer = Dense::new(weights, Some(Vector::from_vec(vec![0.5])), None);
+
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[2, 2, 2]),
+            vec![1.0, 2.0, 5.0, 3.0, 0.0, 0.0, -1.0, 1.0],
+        )
+        .unwrap();
+
+        let output = dense_layer.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[2, 2, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[-0.5, 2.5, 0.5, -1.5]);
+    }
+
+    #[test]
+    fn test_dense_fold_batch_normalization() {
+        let weights = Matrix::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let mut dense_layer = Dense::new(weights, Some(Vector::from_vec(vec![1.0, -1.0])), None);
+        let batch_normalization = BatchNormalization::new(
+            -1,
+            None,
+            Some(Vector::from_vec(vec![2.0, 1.0])),
+            Some(Vector::from_vec(vec![0.5, 0.0])),
+            Vector::from_vec(vec![1.0, -1.0]),
+            Vector::from_vec(vec![4.0, 0.25]),
+            0.0,
+        );
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[1, 2]), vec![1.0, -2.0]).unwrap();
+        let expected = batch_normalization
+            .compute(dense_layer.compute(input.clone()).unwrap())
+            .unwrap();
+
+        assert!(dense_layer.fold_batch_normalization(&batch_normalization));
+
+        let output = dense_layer.compute(input).unwrap();
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_dense_fold_batch_normalization_with_other_channels() {
+        let weights = Matrix::from_shape_vec((2, 2), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+        let mut dense_layer = Dense::new(weights.clone(), Some(Vector::zeros(2)), None);
+        let batch_normalization =
+            BatchNormalization::new(-1, None, None, None, Vector::zeros(3), Vector::ones(3), 0.0);
+
+        assert!(!dense_layer.fold_batch_normalization(&batch_normalization));
+        assert_eq!(dense_layer.weights(), &weights);
+    }
+
+    #[test]
+    fn test_dense_output_shape() {
+        let dense_layer = Dense::new(Matrix::zeros((3, 2)), Some(Vector::zeros(2)), None);
+
+        let output_shape = dense_layer.output_shape(&[None, Some(4), Some(3)]).unwrap();
+        let error = dense_layer.output_shape(&[None, Some(5)]).unwrap_err();
+
+        assert_eq!(output_shape, vec![None, Some(4), Some(2)]);
+        assert_eq!(
+            error.to_string(),
+            "Dense can't take an input of shape (None, 5): \
+             the weights expect a size of 3 on axis 1, got 5"
+        );
+    }
+
+    #[test]
+    fn test_dense_param_count_without_bias() {
+        let with_bias = Dense::new(Matrix::ones((3, 2)), Some(Vector::ones(2)), None);
+        let without_bias = Dense::new(Matrix::ones((3, 2)), None, None);
+
+        let input = NArray::ones(ndarray::IxDyn(&[1, 3]));
+        let output = without_bias.compute(input).unwrap();
+
+        assert_eq!(with_bias.param_count(), ParamCount::trainable(8));
+        assert_eq!(without_bias.param_count(), ParamCount::trainable(6));
+        assert_eq!(output.as_slice().unwrap(), &[3.0, 3.0]);
+    }
 }
@@ -0,0 +1,103 @@
+use crate::layer::{Layer, LayerError, Mask, NdResult, ParamCount};
+use crate::{Matrix, NArray};
+use ndarray::{ArrayD, Axis, ErrorKind, ShapeError};
+
+/// Keras `Embedding` layer, replacing every index with its row of the embedding matrix.
+///
+/// With `mask_zero` the index 0 is padding, and the timesteps holding it are masked for the
+/// following layers.
+pub struct Embedding {
+    embeddings: Matrix,
+    mask_zero: bool,
+}
+
+impl Embedding {
+    pub fn new(embeddings: Matrix, mask_zero: bool) -> Self {
+        Self {
+            embeddings,
+            mask_zero,
+        }
+    }
+
+    /// Looks up integer `indices`, the output has one more axis holding the embeddings.
+    pub fn lookup(&self, indices: &ArrayD<usize>) -> NdResult {
+        if indices
+            .iter()
+            .any(|&index| index >= self.embeddings.nrows())
+        {
+            return Err(ShapeError::from_kind(ErrorKind::OutOfBounds).into());
+        }
+        let flat: Vec<usize> = indices.iter().copied().collect();
+        let output = self.embeddings.select(Axis(0), &flat);
+        let shape: Vec<usize> = indices
+            .shape()
+            .iter()
+            .copied()
+            .chain([self.embeddings.ncols()])
+            .collect();
+        Ok(output.into_shape(shape)?)
+    }
+}
+
+impl Layer for Embedding {
+    fn param_count(&self) -> ParamCount {
+        ParamCount::trainable(self.embeddings.len())
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        // Models only carry `f32` arrays, so the indices come as whole numbers.
+        if incoming.iter().any(|&x| x < 0.0 || x.fract() != 0.0) {
+            return Err(ShapeError::from_kind(ErrorKind::OutOfBounds).into());
+        }
+        self.lookup(&incoming.mapv(|x| x as usize))
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let mut output_shape = input_shape.to_vec();
+        output_shape.push(Some(self.embeddings.ncols()));
+        Ok(output_shape)
+    }
+
+    fn compute_mask(&self, incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        self.mask_zero.then(|| incoming.mapv(|x| x != 0.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn embedding() -> Embedding {
+        Embedding::new(
+            Matrix::from_shape_vec((3, 2), vec![0.0, 0.0, 1.0, 2.0, 3.0, 4.0]).unwrap(),
+            true,
+        )
+    }
+
+    #[test]
+    fn embedding_lookup_and_mask() {
+        let indices =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![2.0, 1.0, 1.0, 0.0]).unwrap();
+        let layer = embedding();
+
+        let mask = layer.compute_mask(&indices, None).unwrap();
+        let output = layer.compute(indices).unwrap();
+
+        assert_eq!(output.shape(), &[2, 2, 2]);
+        assert_eq!(
+            output.as_slice().unwrap(),
+            &[3.0, 4.0, 1.0, 2.0, 1.0, 2.0, 0.0, 0.0]
+        );
+        assert_eq!(mask.as_slice().unwrap(), &[true, true, true, false]);
+    }
+
+    #[test]
+    fn embedding_rejects_unknown_index() {
+        let indices = ArrayD::from_elem(ndarray::IxDyn(&[1, 1]), 3);
+
+        assert!(embedding().lookup(&indices).is_err());
+    }
+}
@@ -1,11 +1,118 @@
-use crate::layer::{Layer, NdResult};
-use crate::{NArray, Vector};
+use crate::layer::spatial::DataFormat;
+use crate::layer::{Layer, LayerError, Mask, NdResult};
+use crate::NArray;
 
-pub struct Flatten;
+/// Keras `Flatten` layer, collapses every axis but the leading batch one. As in Keras,
+/// channels-first inputs are moved to channels-last first, so the order of the features
+/// doesn't depend on `data_format`.
+#[derive(Default)]
+pub struct Flatten {
+    data_format: DataFormat,
+}
+
+impl Flatten {
+    pub fn new(data_format: DataFormat) -> Self {
+        Self { data_format }
+    }
+}
 
 impl Layer for Flatten {
     fn compute(&self, incoming: NArray) -> NdResult {
-        let raw_vec = incoming.into_raw_vec();
-        (Vector::from_vec(raw_vec)).into_dimensionality()
+        let Some(&batch) = incoming.shape().first() else {
+            return Err(LayerError::incompatible_input(
+                "Flatten",
+                incoming.shape(),
+                String::from("the input has no batch axis"),
+            ));
+        };
+        let incoming = if incoming.ndim() >= 3 {
+            self.data_format.to_channels_last(incoming)
+        } else {
+            incoming
+        };
+        let features = incoming.shape()[1..].iter().product();
+        Ok(incoming
+            .as_standard_layout()
+            .into_owned()
+            .into_shape(vec![batch, features])?)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let Some((&batch, sample)) = input_shape.split_first() else {
+            return Err(LayerError::incompatible_shape(
+                "Flatten",
+                input_shape,
+                String::from("the input has no batch axis"),
+            ));
+        };
+        let features = sample
+            .iter()
+            .try_fold(1, |product, size| size.map(|size| product * size));
+        Ok(vec![batch, features])
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> NArray {
+        NArray::from_shape_fn(ndarray::IxDyn(&[2, 3, 2]), |index| {
+            (index[0] * 6 + index[1] * 2 + index[2]) as f32
+        })
+    }
+
+    #[test]
+    fn flatten_keeps_batch_axis() {
+        let output = Flatten::default().compute(input()).unwrap();
+
+        assert_eq!(output.shape(), &[2, 6]);
+        assert_eq!(output[[1, 0]], 6.0);
+    }
+
+    #[test]
+    fn flatten_channels_first() {
+        let output = Flatten::new(DataFormat::ChannelsFirst)
+            .compute(input())
+            .unwrap();
+
+        assert_eq!(output.shape(), &[2, 6]);
+        assert_eq!(
+            &output.as_slice().unwrap()[..6],
+            &[0.0, 2.0, 4.0, 1.0, 3.0, 5.0]
+        );
+    }
+
+    #[test]
+    fn flatten_without_batch_axis() {
+        let error = Flatten::default()
+            .compute(NArray::zeros(ndarray::IxDyn(&[])))
+            .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Flatten can't handle an input of shape []: the input has no batch axis"
+        );
+    }
+
+    #[test]
+    fn flatten_output_shape() {
+        let flatten = Flatten::default();
+
+        assert_eq!(
+            flatten.output_shape(&[None, Some(3), Some(2)]).unwrap(),
+            vec![None, Some(6)]
+        );
+        assert_eq!(
+            flatten.output_shape(&[None, None, Some(2)]).unwrap(),
+            vec![None, None]
+        );
     }
 }
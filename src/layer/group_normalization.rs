@@ -0,0 +1,183 @@
+use crate::layer::layer_normalization::standardize;
+use crate::layer::{expect_size, normalize_axis, Layer, LayerError, NdResult, ParamCount};
+use crate::{NArray, Vector};
+use ndarray::{s, ErrorKind, ShapeError};
+
+/// Keras `GroupNormalization` layer. The channels on `axis` are split in `groups`, each one
+/// standardized over its channels and every spatial position of a sample.
+pub struct GroupNormalization {
+    groups: usize,
+    axis: isize,
+    epsilon: f32,
+    gamma: Option<Vector>,
+    beta: Option<Vector>,
+}
+
+impl GroupNormalization {
+    pub fn new(
+        groups: usize,
+        axis: isize,
+        epsilon: f32,
+        gamma: Option<Vector>,
+        beta: Option<Vector>,
+    ) -> Self {
+        Self {
+            groups,
+            axis,
+            epsilon,
+            gamma,
+            beta,
+        }
+    }
+}
+
+impl Layer for GroupNormalization {
+    fn param_count(&self) -> ParamCount {
+        let count = |parameter: &Option<Vector>| parameter.as_ref().map_or(0, Vector::len);
+        ParamCount::trainable(count(&self.gamma) + count(&self.beta))
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let error = |reason: String| {
+            LayerError::incompatible_shape("GroupNormalization", input_shape, reason)
+        };
+        let axis = match normalize_axis(self.axis, input_shape.len()) {
+            Ok(axis) if axis > 0 => axis,
+            _ => {
+                return Err(error(format!(
+                    "the input has no channels axis {}",
+                    self.axis
+                )))
+            }
+        };
+        if let Some(parameter) = self.gamma.as_ref().or(self.beta.as_ref()) {
+            expect_size("GroupNormalization", input_shape, axis, parameter.len())?;
+        }
+        match input_shape[axis] {
+            Some(channels) if self.groups == 0 || !channels.is_multiple_of(self.groups) => {
+                Err(error(format!(
+                    "{channels} channels can't be split in {} groups",
+                    self.groups
+                )))
+            }
+            _ => Ok(input_shape.to_vec()),
+        }
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let rank = incoming.ndim();
+        let axis = normalize_axis(self.axis, rank)?;
+        let channels = incoming.shape()[axis];
+        if axis == 0 || self.groups == 0 || !channels.is_multiple_of(self.groups) {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        // `[batch, positions, groups, channels per group]` with the channels moved last.
+        let order: Vec<usize> = (0..rank).filter(|&a| a != axis).chain([axis]).collect();
+        let permuted = incoming.permuted_axes(order.clone());
+        let permuted_shape = permuted.shape().to_vec();
+        let batch = permuted_shape[0];
+        let positions = permuted.len() / (batch * channels).max(1);
+        let mut grouped = permuted.as_standard_layout().into_owned().into_shape((
+            batch,
+            positions,
+            self.groups,
+            channels / self.groups,
+        ))?;
+
+        for sample in 0..batch {
+            for group in 0..self.groups {
+                standardize(grouped.slice_mut(s![sample, .., group, ..]), self.epsilon);
+            }
+        }
+        let mut output = grouped.into_shape((batch, positions, channels))?;
+        for parameter in [&self.gamma, &self.beta].into_iter().flatten() {
+            if parameter.len() != channels {
+                return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+            }
+        }
+        if let Some(gamma) = &self.gamma {
+            output *= gamma;
+        }
+        if let Some(beta) = &self.beta {
+            output += beta;
+        }
+
+        let mut inverse = vec![0; rank];
+        for (position, &axis) in order.iter().enumerate() {
+            inverse[axis] = position;
+        }
+        Ok(output.into_shape(permuted_shape)?.permuted_axes(inverse))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn group_normalization_two_groups() {
+        let layer = GroupNormalization::new(2, -1, 1e-3, None, None);
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[1, 2, 4]),
+            vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 9.0],
+        )
+        .unwrap();
+
+        let output = layer.compute(input).unwrap();
+
+        let expected = [
+            -1.212535, -0.7275213, -1.153012, -0.7337349, 0.7275213, 1.212535, 0.5240963, 1.36265,
+        ];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-5);
+        }
+    }
+
+    #[test]
+    fn group_normalization_channels_first_with_gamma_and_beta() {
+        let layer = GroupNormalization::new(
+            1,
+            1,
+            0.0,
+            Some(Vector::from_vec(vec![2.0, 1.0])),
+            Some(Vector::from_vec(vec![0.0, 1.0])),
+        );
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[1, 2, 2]), vec![1.0, 1.0, 3.0, 3.0]).unwrap();
+
+        let output = layer.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 2, 2]);
+        let expected = [-2.0, -2.0, 2.0, 2.0];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn group_normalization_output_shape() {
+        let layer = GroupNormalization::new(2, -1, 1e-3, Some(Vector::ones(4)), None);
+
+        let output_shape = layer.output_shape(&[None, Some(3), Some(4)]).unwrap();
+        let error = layer.output_shape(&[None, Some(3), Some(6)]).unwrap_err();
+        let uneven = GroupNormalization::new(4, -1, 1e-3, None, None)
+            .output_shape(&[None, Some(6)])
+            .unwrap_err();
+
+        assert_eq!(output_shape, vec![None, Some(3), Some(4)]);
+        assert_eq!(
+            error.to_string(),
+            "GroupNormalization can't take an input of shape (None, 3, 6): \
+             the weights expect a size of 4 on axis 2, got 6"
+        );
+        assert_eq!(
+            uneven.to_string(),
+            "GroupNormalization can't take an input of shape (None, 6): \
+             6 channels can't be split in 4 groups"
+        );
+    }
+}
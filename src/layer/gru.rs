@@ -0,0 +1,201 @@
+use crate::layer::recurrent::{
+    activate, cell_output_shape, gate, run_cell, RecurrentCell, RecurrentOptions,
+};
+use crate::layer::{ActivationFunction, Layer, LayerError, Mask, NdResult, ParamCount};
+use crate::{Matrix, NArray, Vector};
+use ndarray::{s, ArrayView2, ShapeError};
+
+/// Keras `GRU` layer. The kernels pack the update, reset and candidate gates in this order.
+///
+/// With `reset_after` the bias has two rows, the first one is added to the input part and the
+/// second one to the recurrent part, and the reset gate is applied after the recurrent
+/// kernel. Otherwise the bias has a single row and the reset gate scales the previous state.
+pub struct Gru {
+    kernel: Matrix,
+    recurrent_kernel: Matrix,
+    input_bias: Vector,
+    recurrent_bias: Vector,
+    use_bias: bool,
+    reset_after: bool,
+    activation: ActivationFunction,
+    recurrent_activation: ActivationFunction,
+    options: RecurrentOptions,
+}
+
+impl Gru {
+    /// `bias` is `[2, 3 * units]` when `reset_after` is set and `[1, 3 * units]` otherwise.
+    pub fn new(
+        kernel: Matrix,
+        recurrent_kernel: Matrix,
+        bias: Option<Matrix>,
+        reset_after: bool,
+        activation: ActivationFunction,
+        recurrent_activation: ActivationFunction,
+        options: RecurrentOptions,
+    ) -> Self {
+        let use_bias = bias.is_some();
+        let bias = bias.unwrap_or_else(|| Matrix::zeros((1, recurrent_kernel.ncols())));
+        let input_bias = bias.row(0).to_owned();
+        let recurrent_bias = if bias.nrows() > 1 {
+            bias.row(1).to_owned()
+        } else {
+            Vector::zeros(bias.ncols())
+        };
+        Self {
+            kernel,
+            recurrent_kernel,
+            input_bias,
+            recurrent_bias,
+            use_bias,
+            reset_after,
+            activation,
+            recurrent_activation,
+            options,
+        }
+    }
+}
+
+impl RecurrentCell for Gru {
+    fn units(&self) -> usize {
+        self.recurrent_kernel.nrows()
+    }
+
+    fn features(&self) -> usize {
+        self.kernel.nrows()
+    }
+
+    fn state_count(&self) -> usize {
+        1
+    }
+
+    fn project_inputs(&self, inputs: &Matrix) -> Matrix {
+        inputs.dot(&self.kernel) + &self.input_bias
+    }
+
+    fn step(&self, projected: ArrayView2<f32>, states: &mut [Matrix]) -> Result<(), ShapeError> {
+        let units = self.units();
+        let projected = projected.to_owned();
+        let state = &states[0];
+        let (update, candidate) = if self.reset_after {
+            let recurrent = state.dot(&self.recurrent_kernel) + &self.recurrent_bias;
+            let update = gate(&projected, 0, units) + gate(&recurrent, 0, units);
+            let reset = gate(&projected, 1, units) + gate(&recurrent, 1, units);
+            let reset = activate(&self.recurrent_activation, reset)?;
+            let candidate = gate(&projected, 2, units) + reset * gate(&recurrent, 2, units);
+            (update, candidate)
+        } else {
+            let recurrent = state.dot(&self.recurrent_kernel.slice(s![.., ..2 * units]));
+            let update = gate(&projected, 0, units) + gate(&recurrent, 0, units);
+            let reset = gate(&projected, 1, units) + gate(&recurrent, 1, units);
+            let reset = activate(&self.recurrent_activation, reset)?;
+            let candidate = gate(&projected, 2, units)
+                + (reset * state).dot(&self.recurrent_kernel.slice(s![.., 2 * units..]));
+            (update, candidate)
+        };
+        let update = activate(&self.recurrent_activation, update)?;
+        let candidate = activate(&self.activation, candidate)?;
+
+        states[0] = &update * state + (1.0 - update) * candidate;
+        Ok(())
+    }
+}
+
+impl Layer for Gru {
+    fn param_count(&self) -> ParamCount {
+        // Without `reset_after` Keras has no recurrent bias.
+        let bias = match (self.use_bias, self.reset_after) {
+            (false, _) => 0,
+            (true, true) => self.input_bias.len() + self.recurrent_bias.len(),
+            (true, false) => self.input_bias.len(),
+        };
+        ParamCount::trainable(self.kernel.len() + self.recurrent_kernel.len() + bias)
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(self.compute_all(incoming)?.swap_remove(0))
+    }
+
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, None)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        cell_output_shape("GRU", self, &self.options, input_shape)
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, mask: Option<&Mask>) -> Option<Mask> {
+        mask.filter(|_| self.options.return_sequences).cloned()
+    }
+
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn gru(bias: Option<Vec<f32>>, reset_after: bool) -> Gru {
+        Gru::new(
+            Matrix::from_shape_vec((1, 3), vec![0.5, -0.3, 0.8]).unwrap(),
+            Matrix::from_shape_vec((1, 3), vec![0.1, 0.4, -0.6]).unwrap(),
+            bias.map(|bias| Matrix::from_shape_vec((bias.len() / 3, 3), bias).unwrap()),
+            reset_after,
+            ActivationFunction::Tanh,
+            ActivationFunction::Sigmoid,
+            RecurrentOptions {
+                return_sequences: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn sequence() -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(&[1, 3, 1]), vec![1.0, 2.0, -1.0]).unwrap()
+    }
+
+    #[test]
+    fn gru_reset_after() {
+        let layer = gru(Some(vec![0.1, 0.2, -0.1, 0.0, -0.2, 0.3]), true);
+
+        let output = layer.compute(sequence()).unwrap();
+
+        assert_eq!(output.shape(), &[1, 3, 1]);
+        let expected = [0.2406778, 0.4061081, -0.2447092];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn gru_reset_before() {
+        let layer = gru(Some(vec![0.1, 0.2, -0.1]), false);
+
+        let output = layer.compute(sequence()).unwrap();
+
+        let expected = [0.2141539, 0.3814349, -0.3043977];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn gru_param_count() {
+        let reset_after = gru(Some(vec![0.1, 0.2, -0.1, 0.0, -0.2, 0.3]), true);
+        let reset_before = gru(Some(vec![0.1, 0.2, -0.1]), false);
+        let without_bias = gru(None, true);
+
+        assert_eq!(reset_after.param_count(), ParamCount::trainable(12));
+        assert_eq!(reset_before.param_count(), ParamCount::trainable(9));
+        assert_eq!(without_bias.param_count(), ParamCount::trainable(6));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::layer::{expect_size, normalize_axis, Layer, LayerError, NdResult, ParamCount};
+use crate::{Matrix, NArray, Vector};
+use ndarray::{ArrayViewMut, Dimension, ErrorKind, ShapeError};
+
+/// Keras `LayerNormalization` layer, standardizing every sample over `axes`.
+///
+/// `gamma` and `beta` hold one value per element of the normalized axes, flattened in the
+/// order of `axes`. They are `None` when Keras was told not to `scale` or `center`.
+pub struct LayerNormalization {
+    axes: Vec<isize>,
+    epsilon: f32,
+    gamma: Option<Vector>,
+    beta: Option<Vector>,
+}
+
+impl LayerNormalization {
+    pub fn new(
+        axes: Vec<isize>,
+        epsilon: f32,
+        gamma: Option<Vector>,
+        beta: Option<Vector>,
+    ) -> Self {
+        Self {
+            axes,
+            epsilon,
+            gamma,
+            beta,
+        }
+    }
+}
+
+impl Layer for LayerNormalization {
+    fn param_count(&self) -> ParamCount {
+        let count = |parameter: &Option<Vector>| parameter.as_ref().map_or(0, Vector::len);
+        ParamCount::trainable(count(&self.gamma) + count(&self.beta))
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let axes = self
+            .axes
+            .iter()
+            .map(|&axis| {
+                normalize_axis(axis, input_shape.len()).map_err(|_| {
+                    LayerError::incompatible_shape(
+                        "LayerNormalization",
+                        input_shape,
+                        format!("the input has no axis {axis}"),
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(features) = self.gamma.as_ref().or(self.beta.as_ref()).map(Vector::len) else {
+            return Ok(input_shape.to_vec());
+        };
+        if let [axis] = axes[..] {
+            expect_size("LayerNormalization", input_shape, axis, features)?;
+        }
+        let sizes: Option<Vec<usize>> = axes.iter().map(|&axis| input_shape[axis]).collect();
+        match sizes.map(|sizes| sizes.iter().product::<usize>()) {
+            Some(size) if size != features => Err(LayerError::incompatible_shape(
+                "LayerNormalization",
+                input_shape,
+                format!("the weights expect {features} values over axes {axes:?}, got {size}"),
+            )),
+            _ => Ok(input_shape.to_vec()),
+        }
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let rank = incoming.ndim();
+        let axes = self
+            .axes
+            .iter()
+            .map(|&axis| normalize_axis(axis, rank))
+            .collect::<Result<Vec<_>, _>>()?;
+        let features: usize = axes.iter().map(|&axis| incoming.shape()[axis]).product();
+        // Moves the normalized axes to the end so every row of the matrix is one group.
+        let order: Vec<usize> = (0..rank)
+            .filter(|axis| !axes.contains(axis))
+            .chain(axes.iter().copied())
+            .collect();
+        let permuted = incoming.permuted_axes(order.clone());
+        let permuted_shape = permuted.shape().to_vec();
+        let mut rows: Matrix = permuted.as_standard_layout().into_owned().into_shape((
+            permuted_shape.iter().product::<usize>() / features.max(1),
+            features,
+        ))?;
+
+        for row in rows.rows_mut() {
+            standardize(row, self.epsilon);
+        }
+        for parameter in [&self.gamma, &self.beta].into_iter().flatten() {
+            if parameter.len() != features {
+                return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+            }
+        }
+        if let Some(gamma) = &self.gamma {
+            rows *= gamma;
+        }
+        if let Some(beta) = &self.beta {
+            rows += beta;
+        }
+
+        let mut inverse = vec![0; rank];
+        for (position, &axis) in order.iter().enumerate() {
+            inverse[axis] = position;
+        }
+        Ok(rows.into_shape(permuted_shape)?.permuted_axes(inverse))
+    }
+}
+
+/// Shifts and scales `values` in place to zero mean and unit variance.
+pub(crate) fn standardize<D: Dimension>(mut values: ArrayViewMut<f32, D>, epsilon: f32) {
+    let mean = values.mean().unwrap_or_default();
+    let variance = values
+        .mapv(|x| (x - mean).powi(2))
+        .mean()
+        .unwrap_or_default();
+    let deviation = (variance + epsilon).sqrt();
+    values.mapv_inplace(|x| (x - mean) / deviation);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    #[test]
+    fn layer_normalization_last_axis() {
+        let layer = LayerNormalization::new(
+            vec![-1],
+            1e-3,
+            Some(Vector::from_vec(vec![1.0, 2.0, 0.5])),
+            Some(Vector::from_vec(vec![0.0, 0.5, -1.0])),
+        );
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[1, 3]), vec![1.0, 2.0, 4.0]).unwrap();
+
+        let output = layer.compute(input).unwrap();
+
+        let expected = [-1.068702, -0.03435076, -0.3320616];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-5);
+        }
+    }
+
+    #[test]
+    fn layer_normalization_several_axes() {
+        let layer = LayerNormalization::new(vec![1, 2], 1e-3, None, None);
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[1, 2, 2]), vec![0.0, 0.0, 3.0, -3.0]).unwrap();
+
+        let output = layer.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 2, 2]);
+        let expected = [0.0, 0.0, 1.414056, -1.414056];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-5);
+        }
+    }
+
+    #[test]
+    fn layer_normalization_output_shape() {
+        let layer = LayerNormalization::new(vec![1, 2], 1e-3, Some(Vector::ones(6)), None);
+
+        let output_shape = layer.output_shape(&[None, Some(2), Some(3)]).unwrap();
+        let error = layer.output_shape(&[None, Some(2), Some(4)]).unwrap_err();
+        let single_axis = LayerNormalization::new(vec![-1], 1e-3, None, Some(Vector::zeros(3)))
+            .output_shape(&[None, Some(4)])
+            .unwrap_err();
+
+        assert_eq!(output_shape, vec![None, Some(2), Some(3)]);
+        assert_eq!(
+            error.to_string(),
+            "LayerNormalization can't take an input of shape (None, 2, 4): \
+             the weights expect 6 values over axes [1, 2], got 8"
+        );
+        assert_eq!(
+            single_axis.to_string(),
+            "LayerNormalization can't take an input of shape (None, 4): \
+             the weights expect a size of 3 on axis 1, got 4"
+        );
+    }
+}
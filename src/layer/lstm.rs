@@ -0,0 +1,165 @@
+use crate::layer::recurrent::{
+    activate, cell_output_shape, gate, run_cell, RecurrentCell, RecurrentOptions,
+};
+use crate::layer::{ActivationFunction, Layer, LayerError, Mask, NdResult, ParamCount};
+use crate::{Matrix, NArray, Vector};
+use ndarray::{ArrayView2, ShapeError};
+
+/// Keras `LSTM` layer. The kernels pack the input, forget, cell and output gates in this order.
+///
+/// `unit_forget_bias` only changes how Keras initializes the bias, so the loaded bias
+/// already accounts for it.
+pub struct Lstm {
+    kernel: Matrix,
+    recurrent_kernel: Matrix,
+    bias: Vector,
+    use_bias: bool,
+    activation: ActivationFunction,
+    recurrent_activation: ActivationFunction,
+    options: RecurrentOptions,
+}
+
+impl Lstm {
+    pub fn new(
+        kernel: Matrix,
+        recurrent_kernel: Matrix,
+        bias: Option<Vector>,
+        activation: ActivationFunction,
+        recurrent_activation: ActivationFunction,
+        options: RecurrentOptions,
+    ) -> Self {
+        let use_bias = bias.is_some();
+        let bias = bias.unwrap_or_else(|| Vector::zeros(recurrent_kernel.ncols()));
+        Self {
+            kernel,
+            recurrent_kernel,
+            bias,
+            use_bias,
+            activation,
+            recurrent_activation,
+            options,
+        }
+    }
+}
+
+impl RecurrentCell for Lstm {
+    fn units(&self) -> usize {
+        self.recurrent_kernel.nrows()
+    }
+
+    fn features(&self) -> usize {
+        self.kernel.nrows()
+    }
+
+    fn state_count(&self) -> usize {
+        2
+    }
+
+    fn project_inputs(&self, inputs: &Matrix) -> Matrix {
+        inputs.dot(&self.kernel) + &self.bias
+    }
+
+    fn step(&self, projected: ArrayView2<f32>, states: &mut [Matrix]) -> Result<(), ShapeError> {
+        let units = self.units();
+        let z = &projected + &states[0].dot(&self.recurrent_kernel);
+        let input_gate = activate(&self.recurrent_activation, gate(&z, 0, units))?;
+        let forget_gate = activate(&self.recurrent_activation, gate(&z, 1, units))?;
+        let candidate = activate(&self.activation, gate(&z, 2, units))?;
+        let output_gate = activate(&self.recurrent_activation, gate(&z, 3, units))?;
+
+        let cell_state = forget_gate * &states[1] + input_gate * candidate;
+        states[0] = output_gate * activate(&self.activation, cell_state.clone())?;
+        states[1] = cell_state;
+        Ok(())
+    }
+}
+
+impl Layer for Lstm {
+    fn param_count(&self) -> ParamCount {
+        let bias = if self.use_bias { self.bias.len() } else { 0 };
+        ParamCount::trainable(self.kernel.len() + self.recurrent_kernel.len() + bias)
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(self.compute_all(incoming)?.swap_remove(0))
+    }
+
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, None)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        cell_output_shape("LSTM", self, &self.options, input_shape)
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, mask: Option<&Mask>) -> Option<Mask> {
+        mask.filter(|_| self.options.return_sequences).cloned()
+    }
+
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn lstm(options: RecurrentOptions) -> Lstm {
+        Lstm::new(
+            Matrix::from_shape_vec((1, 4), vec![0.5, -0.3, 0.8, 0.2]).unwrap(),
+            Matrix::from_shape_vec((1, 4), vec![0.1, 0.4, -0.6, 0.3]).unwrap(),
+            Some(Vector::from_vec(vec![0.0, 1.0, 0.1, -0.1])),
+            ActivationFunction::Tanh,
+            ActivationFunction::Sigmoid,
+            options,
+        )
+    }
+
+    fn sequence() -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(&[1, 3, 1]), vec![1.0, 2.0, -1.0]).unwrap()
+    }
+
+    #[test]
+    fn lstm_return_sequences() {
+        let layer = lstm(RecurrentOptions {
+            return_sequences: true,
+            ..Default::default()
+        });
+
+        let output = layer.compute(sequence()).unwrap();
+
+        assert_eq!(output.shape(), &[1, 3, 1]);
+        let expected = [0.2197013, 0.436924, 0.2059169];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn lstm_go_backwards_return_state() {
+        let layer = lstm(RecurrentOptions {
+            return_state: true,
+            go_backwards: true,
+            ..Default::default()
+        });
+
+        let outputs = layer
+            .compute_all(sequence().remove_axis(ndarray::Axis(0)))
+            .unwrap();
+
+        assert_eq!(outputs.len(), 3);
+        assert_eq!(outputs[0].shape(), &[1]);
+        assert_approx_eq!(outputs[0][[0]], 0.3548003, 1e-6);
+        assert_approx_eq!(outputs[1][[0]], 0.3548003, 1e-6);
+        assert_approx_eq!(outputs[2][[0]], 0.7744708, 1e-6);
+    }
+}
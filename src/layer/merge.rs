@@ -0,0 +1,401 @@
+use crate::layer::{normalize_axis, Layer, LayerError, Mask, NdResult};
+use crate::{Matrix, NArray};
+use ndarray::Axis;
+
+/// Element-wise operation of the Keras merge layers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOperation {
+    Add,
+    Subtract,
+    Multiply,
+    Average,
+    Maximum,
+    Minimum,
+}
+
+impl MergeOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            MergeOperation::Add => "Add",
+            MergeOperation::Subtract => "Subtract",
+            MergeOperation::Multiply => "Multiply",
+            MergeOperation::Average => "Average",
+            MergeOperation::Maximum => "Maximum",
+            MergeOperation::Minimum => "Minimum",
+        }
+    }
+
+    fn combine(&self, accumulated: f32, value: f32) -> f32 {
+        match self {
+            MergeOperation::Add | MergeOperation::Average => accumulated + value,
+            MergeOperation::Subtract => accumulated - value,
+            MergeOperation::Multiply => accumulated * value,
+            MergeOperation::Maximum => accumulated.max(value),
+            MergeOperation::Minimum => accumulated.min(value),
+        }
+    }
+}
+
+/// Fails for the merge layers called on a single tensor, they need a list of them.
+fn single_input_error(layer: &'static str, incoming: &NArray) -> LayerError {
+    LayerError::incompatible_input(
+        layer,
+        incoming.shape(),
+        String::from("a merge layer takes a list of inputs"),
+    )
+}
+
+/// Shape all of `shapes` broadcast to, aligning them on their last axis as NumPy does.
+fn broadcast_shape(shapes: &[&[usize]]) -> Option<Vec<usize>> {
+    let rank = shapes.iter().map(|shape| shape.len()).max()?;
+    let mut broadcast = vec![1; rank];
+    for shape in shapes {
+        for (size, &axis_size) in broadcast[rank - shape.len()..].iter_mut().zip(*shape) {
+            match (*size, axis_size) {
+                (_, 1) => {}
+                (1, _) => *size = axis_size,
+                (size, axis_size) if size == axis_size => {}
+                _ => return None,
+            }
+        }
+    }
+    Some(broadcast)
+}
+
+/// `Add`, `Subtract`, `Multiply`, `Average`, `Maximum` and `Minimum`. The inputs are
+/// broadcast to a common shape.
+pub struct Merge {
+    operation: MergeOperation,
+}
+
+impl Merge {
+    pub fn new(operation: MergeOperation) -> Self {
+        Self { operation }
+    }
+}
+
+impl Layer for Merge {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Err(single_input_error(self.operation.name(), &incoming))
+    }
+
+    fn compute_multiple(&self, incoming: Vec<NArray>) -> Result<Vec<NArray>, LayerError> {
+        let count = incoming.len();
+        let valid_count = match self.operation {
+            MergeOperation::Subtract => count == 2,
+            _ => count >= 1,
+        };
+        let (Some(first), true) = (incoming.first(), valid_count) else {
+            return Err(LayerError::incompatible_input(
+                self.operation.name(),
+                &[],
+                format!("got {count} inputs"),
+            ));
+        };
+        let shapes: Vec<_> = incoming.iter().map(NArray::shape).collect();
+        let broadcast_error = || {
+            LayerError::incompatible_input(
+                self.operation.name(),
+                first.shape(),
+                format!("the inputs {shapes:?} can't be broadcast together"),
+            )
+        };
+        let shape = broadcast_shape(&shapes).ok_or_else(broadcast_error)?;
+        let mut output = first
+            .broadcast(shape.clone())
+            .ok_or_else(broadcast_error)?
+            .to_owned();
+        for input in &incoming[1..] {
+            let input = input.broadcast(shape.clone()).ok_or_else(broadcast_error)?;
+            output.zip_mut_with(&input, |accumulated, &value| {
+                *accumulated = self.operation.combine(*accumulated, value)
+            });
+        }
+        if self.operation == MergeOperation::Average {
+            output /= count as f32;
+        }
+        Ok(vec![output])
+    }
+}
+
+/// Keras `Concatenate` layer, joining its inputs along `axis`.
+pub struct Concatenate {
+    axis: isize,
+}
+
+impl Concatenate {
+    pub fn new(axis: isize) -> Self {
+        Self { axis }
+    }
+}
+
+impl Layer for Concatenate {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Err(single_input_error("Concatenate", &incoming))
+    }
+
+    fn compute_multiple(&self, incoming: Vec<NArray>) -> Result<Vec<NArray>, LayerError> {
+        let Some(first) = incoming.first() else {
+            return Err(LayerError::incompatible_input(
+                "Concatenate",
+                &[],
+                String::from("got no inputs"),
+            ));
+        };
+        let axis = normalize_axis(self.axis, first.ndim())?;
+        let views: Vec<_> = incoming.iter().map(NArray::view).collect();
+        ndarray::concatenate(Axis(axis), &views)
+            .map(|output| vec![output])
+            .map_err(|_| {
+                let shapes: Vec<_> = incoming.iter().map(NArray::shape).collect();
+                LayerError::incompatible_input(
+                    "Concatenate",
+                    first.shape(),
+                    format!(
+                        "the inputs {shapes:?} only may differ on axis {}",
+                        self.axis
+                    ),
+                )
+            })
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+/// Keras `Dot` layer, the dot product of two inputs over `axes` for every sample. With
+/// `normalize` the inputs are L2-normalized along these axes first, giving cosine similarities.
+pub struct Dot {
+    axes: [isize; 2],
+    normalize: bool,
+}
+
+impl Dot {
+    pub fn new(axes: [isize; 2], normalize: bool) -> Self {
+        Self { axes, normalize }
+    }
+}
+
+impl Layer for Dot {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Err(single_input_error("Dot", &incoming))
+    }
+
+    fn compute_multiple(&self, incoming: Vec<NArray>) -> Result<Vec<NArray>, LayerError> {
+        let [x, y] = <[NArray; 2]>::try_from(incoming).map_err(|incoming| {
+            LayerError::incompatible_input(
+                "Dot",
+                &[],
+                format!("takes two inputs, got {}", incoming.len()),
+            )
+        })?;
+        let x_axis = normalize_axis(self.axes[0], x.ndim())?;
+        let y_axis = normalize_axis(self.axes[1], y.ndim())?;
+        let batch = x.shape()[0];
+        if x_axis == 0
+            || y_axis == 0
+            || y.shape()[0] != batch
+            || x.shape()[x_axis] != y.shape()[y_axis]
+        {
+            return Err(LayerError::incompatible_input(
+                "Dot",
+                x.shape(),
+                format!(
+                    "axes {:?} don't pair it with the second input of shape {:?}",
+                    self.axes,
+                    y.shape()
+                ),
+            ));
+        }
+        let (x, y) = if self.normalize {
+            (l2_normalize(x, x_axis), l2_normalize(y, y_axis))
+        } else {
+            (x, y)
+        };
+
+        // Every sample becomes a matrix with the contracted axis last for x and first for y.
+        let depth = x.shape()[x_axis];
+        let last = x.ndim() - 1;
+        let x = move_axis(x, x_axis, last);
+        let y = move_axis(y, y_axis, 1);
+        let x_rest: Vec<usize> = x.shape()[1..x.ndim() - 1].to_vec();
+        let y_rest: Vec<usize> = y.shape()[2..].to_vec();
+        let x_rows = x_rest.iter().product::<usize>();
+        let y_columns = y_rest.iter().product::<usize>();
+        let x = x
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((batch, x_rows, depth))?;
+        let y = y
+            .as_standard_layout()
+            .into_owned()
+            .into_shape((batch, depth, y_columns))?;
+        let products: Vec<Matrix> = x
+            .outer_iter()
+            .zip(y.outer_iter())
+            .map(|(x, y)| x.dot(&y))
+            .collect();
+        let views: Vec<_> = products.iter().map(Matrix::view).collect();
+        let output = ndarray::stack(Axis(0), &views)?;
+
+        let mut shape: Vec<usize> = [batch].into_iter().chain(x_rest).chain(y_rest).collect();
+        // As in Keras, a sample reduced to a scalar keeps one axis.
+        if shape.len() == 1 {
+            shape.push(1);
+        }
+        Ok(vec![output.into_shape(shape)?])
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+fn move_axis(array: NArray, from: usize, to: usize) -> NArray {
+    let mut axes: Vec<usize> = (0..array.ndim()).filter(|&axis| axis != from).collect();
+    axes.insert(to, from);
+    array.permuted_axes(axes)
+}
+
+fn l2_normalize(array: NArray, axis: usize) -> NArray {
+    let norm = array
+        .mapv(|x| x * x)
+        .sum_axis(Axis(axis))
+        .mapv(|sum| sum.max(1e-12).sqrt())
+        .insert_axis(Axis(axis));
+    array / norm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn array(shape: &[usize], values: Vec<f32>) -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(shape), values).unwrap()
+    }
+
+    fn inputs() -> Vec<NArray> {
+        vec![
+            array(&[1, 3], vec![1.0, 5.0, -2.0]),
+            array(&[1, 3], vec![4.0, 2.0, 0.0]),
+        ]
+    }
+
+    #[test]
+    fn element_wise_merges() {
+        let merge = |operation| {
+            Merge::new(operation)
+                .compute_multiple(inputs())
+                .unwrap()
+                .swap_remove(0)
+                .into_raw_vec()
+        };
+
+        assert_eq!(merge(MergeOperation::Add), vec![5.0, 7.0, -2.0]);
+        assert_eq!(merge(MergeOperation::Subtract), vec![-3.0, 3.0, -2.0]);
+        assert_eq!(merge(MergeOperation::Multiply), vec![4.0, 10.0, -0.0]);
+        assert_eq!(merge(MergeOperation::Average), vec![2.5, 3.5, -1.0]);
+        assert_eq!(merge(MergeOperation::Maximum), vec![4.0, 5.0, 0.0]);
+        assert_eq!(merge(MergeOperation::Minimum), vec![1.0, 2.0, -2.0]);
+    }
+
+    #[test]
+    fn subtract_needs_two_inputs() {
+        let mut incoming = inputs();
+        incoming.push(array(&[1, 3], vec![0.0; 3]));
+
+        assert!(Merge::new(MergeOperation::Subtract)
+            .compute_multiple(incoming)
+            .is_err());
+    }
+
+    #[test]
+    fn concatenate_last_axis() {
+        let output = Concatenate::new(-1)
+            .compute_multiple(vec![
+                array(&[1, 1], vec![9.0]),
+                array(&[1, 3], vec![1.0, 2.0, 3.0]),
+            ])
+            .unwrap();
+
+        assert_eq!(output[0].shape(), &[1, 4]);
+        assert_eq!(output[0].as_slice().unwrap(), &[9.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn concatenate_reports_mismatch() {
+        let error = Concatenate::new(1)
+            .compute_multiple(vec![
+                array(&[1, 1], vec![9.0]),
+                array(&[2, 1], vec![1.0, 2.0]),
+            ])
+            .unwrap_err();
+
+        assert!(error.to_string().contains("[[1, 1], [2, 1]]"));
+    }
+
+    #[test]
+    fn dot_vectors() {
+        let output = Dot::new([1, 1], false).compute_multiple(inputs()).unwrap();
+
+        assert_eq!(output[0].shape(), &[1, 1]);
+        assert_eq!(output[0].as_slice().unwrap(), &[14.0]);
+    }
+
+    #[test]
+    fn dot_normalized() {
+        let output = Dot::new([-1, -1], true)
+            .compute_multiple(vec![
+                array(&[1, 2], vec![3.0, 4.0]),
+                array(&[1, 2], vec![6.0, 8.0]),
+            ])
+            .unwrap();
+
+        assert!((output[0][[0, 0]] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn dot_sequences() {
+        // [batch, 2, 2] . [batch, 3, 2] over the features gives [batch, 2, 3].
+        let x = array(&[1, 2, 2], vec![1.0, 0.0, 0.0, 1.0]);
+        let y = array(&[1, 3, 2], vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+
+        let output = Dot::new([2, 2], false)
+            .compute_multiple(vec![x, y])
+            .unwrap();
+
+        assert_eq!(output[0].shape(), &[1, 2, 3]);
+        assert_eq!(
+            output[0].as_slice().unwrap(),
+            &[1.0, 3.0, 5.0, 2.0, 4.0, 6.0]
+        );
+    }
+
+    #[test]
+    fn merge_broadcasts_every_input() {
+        let incoming = vec![
+            array(&[2, 1], vec![1.0, 2.0]),
+            array(&[2, 4], vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]),
+            array(&[4], vec![10.0, 20.0, 30.0, 40.0]),
+        ];
+
+        let output = Merge::new(MergeOperation::Add)
+            .compute_multiple(incoming)
+            .unwrap()
+            .swap_remove(0);
+        let error = Merge::new(MergeOperation::Add)
+            .compute_multiple(vec![
+                array(&[2, 3], vec![0.0; 6]),
+                array(&[2, 4], vec![0.0; 8]),
+            ])
+            .unwrap_err();
+
+        assert_eq!(output.shape(), &[2, 4]);
+        assert_eq!(
+            output.into_raw_vec(),
+            vec![11.0, 22.0, 33.0, 44.0, 16.0, 27.0, 38.0, 49.0]
+        );
+        assert!(error.to_string().contains("can't be broadcast together"));
+    }
+}
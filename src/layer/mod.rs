@@ -1,18 +1,289 @@
 pub mod activation_layer;
+pub mod advanced_activations;
+pub mod batch_normalization;
+pub mod bidirectional;
+pub mod conv2d;
 pub mod dense;
+pub mod embedding;
 pub mod flatten;
+pub mod group_normalization;
+pub mod gru;
+pub mod layer_normalization;
+pub mod lstm;
+pub mod merge;
+pub mod multi_head_attention;
+pub mod permute;
+pub mod pooling;
+pub mod recurrent;
+pub mod regularization;
+pub mod repeat_vector;
+pub mod reshape;
+pub mod simple_rnn;
+pub mod spatial;
+pub mod time_distributed;
 
 pub use activation_layer::{Activation, ActivationFunction};
+pub use advanced_activations::{Elu, LeakyRelu, PRelu, Relu, Softmax};
+pub use batch_normalization::BatchNormalization;
+pub use bidirectional::{Bidirectional, MergeMode};
+pub use conv2d::Conv2D;
 pub use dense::Dense;
+pub use embedding::Embedding;
 pub use flatten::Flatten;
+pub use group_normalization::GroupNormalization;
+pub use gru::Gru;
+pub use layer_normalization::LayerNormalization;
+pub use lstm::Lstm;
+pub use merge::{Concatenate, Dot, Merge, MergeOperation};
+pub use multi_head_attention::{AttentionInput, AttentionOptions, MultiHeadAttention};
+pub use permute::Permute;
+pub use pooling::{GlobalPooling, Pooling, PoolingMode};
+pub use recurrent::RecurrentOptions;
+pub use regularization::Regularization;
+pub use repeat_vector::RepeatVector;
+pub use reshape::Reshape;
+pub use simple_rnn::SimpleRnn;
+pub use spatial::{DataFormat, Padding};
+pub use time_distributed::TimeDistributed;
 
 use crate::{Matrix, NArray, Vector};
+use thiserror::Error;
 
-pub type NdResult = Result<NArray, ndarray::ShapeError>; 
+pub type NdResult = Result<NArray, LayerError>;
 
-pub trait Layer {
+#[derive(Debug, Error)]
+pub enum LayerError {
+    #[error("Incompatible array shapes")]
+    ShapeError(#[from] ndarray::ShapeError),
+    #[error("{layer} can't handle an input of shape {shape:?}: {reason}")]
+    IncompatibleInput {
+        layer: &'static str,
+        shape: Vec<usize>,
+        reason: String,
+    },
+    #[error(
+        "{layer} can't take an input of shape {}: {reason}",
+        format_shape(shape)
+    )]
+    IncompatibleShape {
+        layer: &'static str,
+        shape: Vec<Option<usize>>,
+        reason: String,
+    },
+}
+
+impl LayerError {
+    pub fn incompatible_input(layer: &'static str, shape: &[usize], reason: String) -> Self {
+        LayerError::IncompatibleInput {
+            layer,
+            shape: shape.to_vec(),
+            reason,
+        }
+    }
+
+    pub fn incompatible_shape(
+        layer: &'static str,
+        shape: &[Option<usize>],
+        reason: String,
+    ) -> Self {
+        LayerError::IncompatibleShape {
+            layer,
+            shape: shape.to_vec(),
+            reason,
+        }
+    }
+}
+
+/// Number of parameters of a layer, split as Keras reports them in `model.summary()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParamCount {
+    pub trainable: usize,
+    pub non_trainable: usize,
+}
+
+impl ParamCount {
+    pub fn trainable(count: usize) -> Self {
+        Self {
+            trainable: count,
+            non_trainable: 0,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.trainable + self.non_trainable
+    }
+}
+
+impl std::ops::Add for ParamCount {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            trainable: self.trainable + other.trainable,
+            non_trainable: self.non_trainable + other.non_trainable,
+        }
+    }
+}
+
+impl std::iter::Sum for ParamCount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, count| total + count)
+    }
+}
+
+/// Valid timesteps of a `[batch, timesteps]` input, as produced by `Embedding(mask_zero=True)`.
+pub type Mask = ndarray::ArrayD<bool>;
+
+/// Turns a Keras axis, negative ones counting from the end, into an index below `rank`.
+pub fn normalize_axis(axis: isize, rank: usize) -> Result<usize, ndarray::ShapeError> {
+    let normalized = if axis < 0 { axis + rank as isize } else { axis };
+    if (0..rank as isize).contains(&normalized) {
+        Ok(normalized as usize)
+    } else {
+        Err(ndarray::ShapeError::from_kind(
+            ndarray::ErrorKind::OutOfBounds,
+        ))
+    }
+}
+
+/// Writes a static shape the way Keras does, `(None, 28, 28)`.
+pub fn format_shape(shape: &[Option<usize>]) -> String {
+    let sizes: Vec<String> = shape
+        .iter()
+        .map(|size| size.map_or(String::from("None"), |size| size.to_string()))
+        .collect();
+    match sizes.as_slice() {
+        [size] => format!("({size},)"),
+        _ => format!("({})", sizes.join(", ")),
+    }
+}
+
+/// Checks the rank of a static shape given to [`Layer::output_shape`].
+pub fn expect_rank(
+    layer: &'static str,
+    shape: &[Option<usize>],
+    rank: usize,
+    layout: &str,
+) -> Result<(), LayerError> {
+    if shape.len() == rank {
+        Ok(())
+    } else {
+        Err(LayerError::incompatible_shape(
+            layer,
+            shape,
+            format!("expected {layout}"),
+        ))
+    }
+}
+
+/// Checks that the size of `axis` in a static shape, when known, matches the weights.
+pub fn expect_size(
+    layer: &'static str,
+    shape: &[Option<usize>],
+    axis: usize,
+    expected: usize,
+) -> Result<(), LayerError> {
+    match shape.get(axis) {
+        Some(Some(size)) if *size != expected => Err(LayerError::incompatible_shape(
+            layer,
+            shape,
+            format!("the weights expect a size of {expected} on axis {axis}, got {size}"),
+        )),
+        Some(_) => Ok(()),
+        None => Err(LayerError::incompatible_shape(
+            layer,
+            shape,
+            format!("the input has no axis {axis}"),
+        )),
+    }
+}
+
+/// Runs `compute` on an input of `rank` axes, the batch one included. A single sample comes
+/// without the batch axis, so it is batched for `compute` and unbatched afterwards.
+pub fn compute_batched(
+    layer: &'static str,
+    incoming: NArray,
+    rank: usize,
+    compute: impl FnOnce(NArray) -> NdResult,
+) -> NdResult {
+    let batch = ndarray::Axis(0);
+    match incoming.ndim() {
+        ndim if ndim == rank => compute(incoming),
+        ndim if ndim + 1 == rank => Ok(compute(incoming.insert_axis(batch))?.remove_axis(batch)),
+        _ => Err(LayerError::incompatible_input(
+            layer,
+            incoming.shape(),
+            format!("expected {rank} axes, or {} for a single sample", rank - 1),
+        )),
+    }
+}
+
+/// A layer is shared between the threads running a model, hence `Send + Sync`.
+pub trait Layer: Send + Sync {
     fn compute(&self, incoming: NArray) -> NdResult;
 
+    /// Every output of the layer, for layers such as `LSTM(return_state=True)` that return
+    /// more than one tensor. The first one is what [`Layer::compute`] returns.
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        Ok(vec![self.compute(incoming)?])
+    }
+
+    /// Outputs of a layer called on several tensors, such as the merge layers. The other
+    /// layers only accept a single one.
+    fn compute_multiple(&self, mut incoming: Vec<NArray>) -> Result<Vec<NArray>, LayerError> {
+        if incoming.len() != 1 {
+            let shape = incoming.first().map(NArray::shape).unwrap_or_default();
+            return Err(LayerError::incompatible_input(
+                "Layer",
+                shape,
+                format!("takes a single input, got {}", incoming.len()),
+            ));
+        }
+        self.compute_all(incoming.swap_remove(0))
+    }
+
+    /// Static shape of the first output for a batched input of static shape `input_shape`,
+    /// `None` standing for sizes only known when computing, such as the batch. Layers with
+    /// weights also check that the input fits them. The default keeps the shape.
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        Ok(input_shape.to_vec())
+    }
+
+    /// Parameters Keras counts for the layer, none by default.
+    fn param_count(&self) -> ParamCount {
+        ParamCount::default()
+    }
+
+    /// Mask of the output given the `incoming` array and its mask. Layers keeping the
+    /// timesteps pass it along, the other ones override this to drop it.
+    fn compute_mask(&self, _incoming: &NArray, mask: Option<&Mask>) -> Option<Mask> {
+        mask.cloned()
+    }
+
+    /// Same as [`Layer::compute_all`], skipping the timesteps `mask` marks invalid for the
+    /// layers that support it, such as the recurrent ones.
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        _mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        self.compute_all(incoming)
+    }
+
+    /// The layer as a `BatchNormalization`, for the pass folding it into the preceding layer.
+    fn batch_normalization(&self) -> Option<&BatchNormalization> {
+        None
+    }
+
+    /// Folds a `BatchNormalization` applied to the output of this layer into its weights.
+    /// Returns `false`, leaving the layer untouched, when that isn't possible.
+    fn fold_batch_normalization(&mut self, _batch_normalization: &BatchNormalization) -> bool {
+        false
+    }
+
     fn weights_mut(&mut self) -> &mut Matrix {
         panic!("this layer is not trainable")
     }
@@ -0,0 +1,349 @@
+use crate::activations::softmax;
+use crate::layer::{expect_rank, Dense, Layer, LayerError, NdResult, ParamCount};
+use crate::NArray;
+use ndarray::{s, Array3, Array4, Axis, Ix4};
+
+/// Added to the scores of the positions a query may not attend to, as Keras does.
+const MASKED_SCORE: f32 = -1e9;
+
+/// Argument of the Keras call a tensor is passed as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttentionInput {
+    Query,
+    Value,
+    Key,
+    AttentionMask,
+}
+
+impl AttentionInput {
+    /// Order of the positional arguments of `MultiHeadAttention.call`.
+    pub const POSITIONAL: [AttentionInput; 3] = [
+        AttentionInput::Query,
+        AttentionInput::Value,
+        AttentionInput::Key,
+    ];
+
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "query" => Some(AttentionInput::Query),
+            "value" => Some(AttentionInput::Value),
+            "key" => Some(AttentionInput::Key),
+            "attention_mask" => Some(AttentionInput::AttentionMask),
+            _ => None,
+        }
+    }
+}
+
+/// Arguments of the Keras call of a `MultiHeadAttention` layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttentionOptions {
+    /// What each tensor given to [`Layer::compute_multiple`] is, in order.
+    pub inputs: Vec<AttentionInput>,
+    pub use_causal_mask: bool,
+    /// Returns the `[batch, heads, target, source]` scores after the output.
+    pub return_attention_scores: bool,
+}
+
+impl Default for AttentionOptions {
+    fn default() -> Self {
+        Self {
+            inputs: vec![AttentionInput::Query, AttentionInput::Value],
+            use_causal_mask: false,
+            return_attention_scores: false,
+        }
+    }
+}
+
+/// Keras `MultiHeadAttention` layer over `[batch, timesteps, features]` inputs.
+///
+/// The projections are `Dense` layers with the heads flattened into their units, so the
+/// query one maps the features to `num_heads * key_dim` values. Called on a single tensor,
+/// the layer attends over that tensor.
+pub struct MultiHeadAttention {
+    num_heads: usize,
+    query: Dense,
+    key: Dense,
+    value: Dense,
+    output: Dense,
+    options: AttentionOptions,
+}
+
+impl MultiHeadAttention {
+    pub fn new(
+        num_heads: usize,
+        query: Dense,
+        key: Dense,
+        value: Dense,
+        output: Dense,
+        options: AttentionOptions,
+    ) -> Self {
+        Self {
+            num_heads,
+            query,
+            key,
+            value,
+            output,
+            options,
+        }
+    }
+
+    fn attend(
+        &self,
+        query: NArray,
+        value: NArray,
+        key: Option<NArray>,
+        attention_mask: Option<NArray>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        for input in [Some(&query), Some(&value), key.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if input.ndim() != 3 || input.shape()[0] != query.shape()[0] {
+                return Err(LayerError::incompatible_input(
+                    "MultiHeadAttention",
+                    input.shape(),
+                    format!(
+                        "expected [batch, timesteps, features] with the batch of the query {:?}",
+                        query.shape()
+                    ),
+                ));
+            }
+        }
+        let (batch, target, source) = (query.shape()[0], query.shape()[1], value.shape()[1]);
+        let heads = self.num_heads;
+
+        let query = self.query.compute(query)?;
+        let key = self.key.compute(key.unwrap_or_else(|| value.clone()))?;
+        let value = self.value.compute(value)?;
+        let key_dim = query.shape()[2] / heads;
+        let value_dim = value.shape()[2] / heads;
+        let query = query.into_shape((batch, target, heads, key_dim))? / (key_dim as f32).sqrt();
+        let key = key.into_shape((batch, source, heads, key_dim))?;
+        let value = value.into_shape((batch, source, heads, value_dim))?;
+
+        let mut scores = Array4::zeros((batch, heads, target, source));
+        for b in 0..batch {
+            for h in 0..heads {
+                let head_scores = query
+                    .slice(s![b, .., h, ..])
+                    .dot(&key.slice(s![b, .., h, ..]).t());
+                scores.slice_mut(s![b, h, .., ..]).assign(&head_scores);
+            }
+        }
+        if let Some(mask) = self.mask(attention_mask, (batch, target, source))? {
+            let mask = mask.insert_axis(Axis(1));
+            scores.zip_mut_with(&mask, |score, &attend| {
+                if !attend {
+                    *score += MASKED_SCORE;
+                }
+            });
+        }
+        let scores = softmax(scores.into_dyn()).into_dimensionality::<Ix4>()?;
+
+        let mut context = Array4::zeros((batch, target, heads, value_dim));
+        for b in 0..batch {
+            for h in 0..heads {
+                let head_context =
+                    scores
+                        .slice(s![b, h, .., ..])
+                        .dot(&value.slice(s![b, .., h, ..]));
+                context.slice_mut(s![b, .., h, ..]).assign(&head_context);
+            }
+        }
+        let context = context.into_shape((batch, target, heads * value_dim))?;
+        let output = self.output.compute(context.into_dyn())?;
+        Ok(if self.options.return_attention_scores {
+            vec![output, scores.into_dyn()]
+        } else {
+            vec![output]
+        })
+    }
+
+    /// Combines the given `[batch, target, source]` mask with the causal one.
+    fn mask(
+        &self,
+        attention_mask: Option<NArray>,
+        shape: (usize, usize, usize),
+    ) -> Result<Option<Array3<bool>>, LayerError> {
+        let mut mask = match attention_mask {
+            Some(attention_mask) => {
+                let Some(broadcast) = attention_mask.broadcast(shape) else {
+                    return Err(LayerError::incompatible_input(
+                        "MultiHeadAttention",
+                        attention_mask.shape(),
+                        format!("the attention mask doesn't broadcast to {shape:?}"),
+                    ));
+                };
+                Some(broadcast.mapv(|attend| attend != 0.0))
+            }
+            None => None,
+        };
+        if self.options.use_causal_mask {
+            let mask = mask.get_or_insert_with(|| Array3::from_elem(shape, true));
+            for ((_, target, source), attend) in mask.indexed_iter_mut() {
+                *attend &= source <= target;
+            }
+        }
+        Ok(mask)
+    }
+}
+
+impl Layer for MultiHeadAttention {
+    fn param_count(&self) -> ParamCount {
+        [&self.query, &self.key, &self.value, &self.output]
+            .into_iter()
+            .map(Dense::param_count)
+            .sum()
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(self
+            .attend(incoming.clone(), incoming, None, None)?
+            .swap_remove(0))
+    }
+
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        self.attend(incoming.clone(), incoming, None, None)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        expect_rank(
+            "MultiHeadAttention",
+            input_shape,
+            3,
+            "[batch, timesteps, features]",
+        )?;
+        self.query.output_shape(input_shape)?;
+        self.key.output_shape(input_shape)?;
+        let value_shape = self.value.output_shape(input_shape)?;
+        self.output.output_shape(&value_shape)
+    }
+
+    fn compute_multiple(&self, incoming: Vec<NArray>) -> Result<Vec<NArray>, LayerError> {
+        if incoming.len() != self.options.inputs.len() {
+            return Err(LayerError::incompatible_input(
+                "MultiHeadAttention",
+                incoming.first().map(NArray::shape).unwrap_or_default(),
+                format!(
+                    "expected the inputs {:?}, got {} tensors",
+                    self.options.inputs,
+                    incoming.len()
+                ),
+            ));
+        }
+        let (mut query, mut value, mut key, mut attention_mask) = (None, None, None, None);
+        for (role, input) in self.options.inputs.iter().zip(incoming) {
+            let slot = match role {
+                AttentionInput::Query => &mut query,
+                AttentionInput::Value => &mut value,
+                AttentionInput::Key => &mut key,
+                AttentionInput::AttentionMask => &mut attention_mask,
+            };
+            *slot = Some(input);
+        }
+        let Some(query) = query else {
+            return Err(LayerError::incompatible_input(
+                "MultiHeadAttention",
+                &[],
+                String::from("the query is missing"),
+            ));
+        };
+        let value = value.unwrap_or_else(|| query.clone());
+        self.attend(query, value, key, attention_mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Matrix, Vector};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn identity(size: usize) -> Dense {
+        Dense::new(Matrix::eye(size), Some(Vector::zeros(size)), None)
+    }
+
+    fn attention(num_heads: usize, options: AttentionOptions) -> MultiHeadAttention {
+        MultiHeadAttention::new(
+            num_heads,
+            identity(2),
+            identity(2),
+            identity(2),
+            identity(2),
+            options,
+        )
+    }
+
+    fn sequence() -> NArray {
+        NArray::from_shape_vec(
+            ndarray::IxDyn(&[1, 3, 2]),
+            vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn self_attention() {
+        let layer = attention(1, AttentionOptions::default());
+
+        let output = layer.compute(sequence()).unwrap();
+
+        // With identity projections, every query averages the values weighted by
+        // softmax(q . k / sqrt(2)).
+        let weight = |score: f32| (score / 2f32.sqrt()).exp();
+        let total = 2.0 * weight(1.0) + weight(0.0);
+        assert_eq!(output.shape(), &[1, 3, 2]);
+        assert_approx_eq!(output[[0, 0, 0]], 2.0 * weight(1.0) / total, 1e-6);
+        assert_approx_eq!(output[[0, 0, 1]], (weight(0.0) + weight(1.0)) / total, 1e-6);
+    }
+
+    #[test]
+    fn causal_mask_and_scores() {
+        let layer = attention(
+            2,
+            AttentionOptions {
+                use_causal_mask: true,
+                return_attention_scores: true,
+                ..Default::default()
+            },
+        );
+
+        let outputs = layer
+            .compute_multiple(vec![sequence(), sequence()])
+            .unwrap();
+
+        assert_eq!(outputs[1].shape(), &[1, 2, 3, 3]);
+        // The first timestep can only attend to itself.
+        assert_approx_eq!(outputs[1][[0, 0, 0, 0]], 1.0, 1e-6);
+        assert_approx_eq!(outputs[1][[0, 1, 0, 1]], 0.0, 1e-6);
+        assert_eq!(outputs[0].slice(s![0, 0, ..]).to_vec(), vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn attention_mask_input() {
+        let layer = attention(
+            1,
+            AttentionOptions {
+                inputs: vec![
+                    AttentionInput::Query,
+                    AttentionInput::Value,
+                    AttentionInput::AttentionMask,
+                ],
+                ..Default::default()
+            },
+        );
+        // Every query only attends to the last timestep.
+        let mask = NArray::from_shape_vec(ndarray::IxDyn(&[1, 1, 3]), vec![0.0, 0.0, 1.0]).unwrap();
+
+        let output = layer
+            .compute_multiple(vec![sequence(), sequence(), mask])
+            .unwrap();
+
+        for value in output[0].iter() {
+            assert_approx_eq!(value, 1.0, 1e-6);
+        }
+    }
+}
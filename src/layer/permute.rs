@@ -0,0 +1,85 @@
+use crate::layer::{Layer, LayerError, Mask, NdResult};
+use crate::NArray;
+
+/// Keras `Permute` layer. `dims` orders the axes of a sample, starting from 1 as the batch
+/// axis stays in place.
+pub struct Permute {
+    dims: Vec<usize>,
+}
+
+impl Permute {
+    pub fn new(dims: Vec<usize>) -> Self {
+        Self { dims }
+    }
+}
+
+impl Layer for Permute {
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let mut sorted = self.dims.clone();
+        sorted.sort_unstable();
+        if input_shape.is_empty() || !sorted.iter().copied().eq(1..input_shape.len()) {
+            return Err(LayerError::incompatible_shape(
+                "Permute",
+                input_shape,
+                format!(
+                    "dims {:?} must order the axes 1 to {} of the input",
+                    self.dims,
+                    input_shape.len().saturating_sub(1)
+                ),
+            ));
+        }
+        Ok([input_shape[0]]
+            .into_iter()
+            .chain(self.dims.iter().map(|&axis| input_shape[axis]))
+            .collect())
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let mut sorted = self.dims.clone();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(1..incoming.ndim()) {
+            return Err(LayerError::incompatible_input(
+                "Permute",
+                incoming.shape(),
+                format!(
+                    "dims {:?} must order the axes 1 to {} of the input",
+                    self.dims,
+                    incoming.ndim().saturating_sub(1)
+                ),
+            ));
+        }
+        let axes: Vec<usize> = [0].into_iter().chain(self.dims.iter().copied()).collect();
+        Ok(incoming.permuted_axes(axes))
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permute_swaps_axes() {
+        let input = NArray::from_shape_fn(ndarray::IxDyn(&[1, 2, 3]), |index| {
+            (index[1] * 3 + index[2]) as f32
+        });
+
+        let output = Permute::new(vec![2, 1]).compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 3, 2]);
+        assert_eq!(output[[0, 2, 1]], 5.0);
+    }
+
+    #[test]
+    fn permute_rejects_wrong_rank() {
+        let input = NArray::zeros(ndarray::IxDyn(&[1, 2]));
+
+        assert!(Permute::new(vec![2, 1]).compute(input).is_err());
+    }
+}
@@ -0,0 +1,360 @@
+use crate::layer::spatial::{DataFormat, Padding};
+use crate::layer::{compute_batched, expect_rank, Layer, LayerError, Mask, NdResult};
+use crate::NArray;
+use ndarray::{Array4, Axis, ErrorKind, ShapeError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolingMode {
+    Max,
+    Average,
+}
+
+/// `MaxPooling1D/2D` and `AveragePooling1D/2D`, depending on the length of `pool_size`.
+pub struct Pooling {
+    mode: PoolingMode,
+    pool_size: Vec<usize>,
+    strides: Vec<usize>,
+    padding: Padding,
+    data_format: DataFormat,
+}
+
+impl Pooling {
+    pub fn new(
+        mode: PoolingMode,
+        pool_size: Vec<usize>,
+        strides: Vec<usize>,
+        padding: Padding,
+        data_format: DataFormat,
+    ) -> Self {
+        Self {
+            mode,
+            pool_size,
+            strides,
+            padding,
+            data_format,
+        }
+    }
+
+    /// Pools a `[batch, height, width, channels]` array. As in TensorFlow, padded positions
+    /// are skipped, so they never win the max nor count towards the average.
+    fn pool(&self, incoming: NArray, pool_size: [usize; 2], strides: [usize; 2]) -> NdResult {
+        let incoming = incoming.into_dimensionality::<ndarray::Ix4>()?;
+        let (batch, height, width, channels) = incoming.dim();
+        let (output_height, pad_top) = self.padding.output_size(height, pool_size[0], strides[0]);
+        let (output_width, pad_left) = self.padding.output_size(width, pool_size[1], strides[1]);
+
+        let mut output = Array4::zeros((batch, output_height, output_width, channels));
+        for ((sample, output_y, output_x, channel), value) in output.indexed_iter_mut() {
+            let top = (output_y * strides[0]).saturating_sub(pad_top);
+            let bottom = (output_y * strides[0] + pool_size[0] - pad_top).min(height);
+            let left = (output_x * strides[1]).saturating_sub(pad_left);
+            let right = (output_x * strides[1] + pool_size[1] - pad_left).min(width);
+            let window = incoming.slice(ndarray::s![sample, top..bottom, left..right, channel]);
+            *value = match self.mode {
+                PoolingMode::Max => window.fold(f32::NEG_INFINITY, |acc, &x| acc.max(x)),
+                PoolingMode::Average => window.sum() / window.len() as f32,
+            };
+        }
+        Ok(output.into_dyn())
+    }
+}
+
+impl Layer for Pooling {
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let spatial_dims = self.pool_size.len();
+        expect_rank(
+            "Pooling",
+            input_shape,
+            spatial_dims + 2,
+            &format!("a batch axis, {spatial_dims} spatial axes and a channels axis"),
+        )?;
+        let mut output_shape = input_shape.to_vec();
+        for ((axis, pool), stride) in self
+            .data_format
+            .spatial_axes(input_shape.len())
+            .zip(&self.pool_size)
+            .zip(&self.strides)
+        {
+            output_shape[axis] =
+                input_shape[axis].map(|size| self.padding.output_size(size, *pool, *stride).0);
+        }
+        Ok(output_shape)
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let rank = self.pool_size.len() + 2;
+        compute_batched("Pooling", incoming, rank, |incoming| {
+            let incoming = self.data_format.to_channels_last(incoming);
+            let output = match (self.pool_size.as_slice(), self.strides.as_slice()) {
+                ([pool], [stride]) => self
+                    .pool(incoming.insert_axis(Axis(1)), [1, *pool], [1, *stride])?
+                    .remove_axis(Axis(1)),
+                ([pool_y, pool_x], [stride_y, stride_x]) => {
+                    self.pool(incoming, [*pool_y, *pool_x], [*stride_y, *stride_x])?
+                }
+                _ => return Err(ShapeError::from_kind(ErrorKind::Unsupported).into()),
+            };
+            Ok(self.data_format.from_channels_last(output))
+        })
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+/// `GlobalMaxPooling1D/2D` and `GlobalAveragePooling1D/2D`, reducing every spatial axis.
+pub struct GlobalPooling {
+    mode: PoolingMode,
+    spatial_dims: usize,
+    data_format: DataFormat,
+    keepdims: bool,
+}
+
+impl GlobalPooling {
+    pub fn new(
+        mode: PoolingMode,
+        spatial_dims: usize,
+        data_format: DataFormat,
+        keepdims: bool,
+    ) -> Self {
+        Self {
+            mode,
+            spatial_dims,
+            data_format,
+            keepdims,
+        }
+    }
+
+    /// Averages the `[batch, steps, channels]` input over the steps `mask` marks valid, as
+    /// Keras `GlobalAveragePooling1D` does.
+    fn masked_average(&self, incoming: NArray, mask: &Mask) -> NdResult {
+        let incoming = self.data_format.to_channels_last(incoming);
+        if mask.shape() != &incoming.shape()[..2] {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        let weights = mask.mapv(f32::from).insert_axis(Axis(2));
+        let mut output = (incoming * &weights).sum_axis(Axis(1)) / weights.sum_axis(Axis(1));
+        if self.keepdims {
+            output.insert_axis_inplace(Axis(1));
+            output = self.data_format.from_channels_last(output);
+        }
+        Ok(output)
+    }
+}
+
+impl Layer for GlobalPooling {
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let rank = self.spatial_dims + 2;
+        expect_rank(
+            "GlobalPooling",
+            input_shape,
+            rank,
+            &format!(
+                "a batch axis, {} spatial axes and a channels axis",
+                self.spatial_dims
+            ),
+        )?;
+        let spatial_axes = self.data_format.spatial_axes(rank);
+        Ok(if self.keepdims {
+            input_shape
+                .iter()
+                .enumerate()
+                .map(|(axis, size)| {
+                    if spatial_axes.contains(&axis) {
+                        Some(1)
+                    } else {
+                        *size
+                    }
+                })
+                .collect()
+        } else {
+            vec![
+                input_shape[0],
+                input_shape[self.data_format.channels_axis(rank)],
+            ]
+        })
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let rank = self.spatial_dims + 2;
+        compute_batched("GlobalPooling", incoming, rank, |incoming| {
+            let mut output = self.data_format.to_channels_last(incoming);
+            for _ in 0..self.spatial_dims {
+                output = match self.mode {
+                    PoolingMode::Max => {
+                        output.fold_axis(Axis(1), f32::NEG_INFINITY, |acc, &x| acc.max(x))
+                    }
+                    PoolingMode::Average => output
+                        .mean_axis(Axis(1))
+                        .ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleShape))?,
+                };
+            }
+            if self.keepdims {
+                for _ in 0..self.spatial_dims {
+                    output.insert_axis_inplace(Axis(1));
+                }
+                output = self.data_format.from_channels_last(output);
+            }
+            Ok(output)
+        })
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        match mask {
+            Some(mask)
+                if self.mode == PoolingMode::Average
+                    && self.spatial_dims == 1
+                    && incoming.ndim() == 3 =>
+            {
+                Ok(vec![self.masked_average(incoming, mask)?])
+            }
+            _ => self.compute_all(incoming),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image_4x4() -> NArray {
+        NArray::from_shape_vec(
+            ndarray::IxDyn(&[4, 4, 1]),
+            (1..=16).map(|x| x as f32).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn max_pooling_2d() {
+        let pooling = Pooling::new(
+            PoolingMode::Max,
+            vec![2, 2],
+            vec![2, 2],
+            Padding::Valid,
+            DataFormat::ChannelsLast,
+        );
+
+        let output = pooling.compute(image_4x4()).unwrap();
+
+        assert_eq!(output.shape(), &[2, 2, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[6.0, 8.0, 14.0, 16.0]);
+    }
+
+    #[test]
+    fn average_pooling_2d_same_padding_skips_padding() {
+        let pooling = Pooling::new(
+            PoolingMode::Average,
+            vec![2, 2],
+            vec![2, 2],
+            Padding::Same,
+            DataFormat::ChannelsLast,
+        );
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[1, 3, 3, 1]),
+            (1..=9).map(|x| x as f32).collect(),
+        )
+        .unwrap();
+
+        let output = pooling.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 2, 2, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[3.0, 4.5, 7.5, 9.0]);
+    }
+
+    #[test]
+    fn max_pooling_1d_with_strides() {
+        let pooling = Pooling::new(
+            PoolingMode::Max,
+            vec![3],
+            vec![2],
+            Padding::Valid,
+            DataFormat::ChannelsLast,
+        );
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[5, 2]),
+            vec![1.0, -1.0, 5.0, -2.0, 2.0, -3.0, 0.0, -4.0, 3.0, -5.0],
+        )
+        .unwrap();
+
+        let output = pooling.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[2, 2]);
+        assert_eq!(output.as_slice().unwrap(), &[5.0, -1.0, 3.0, -3.0]);
+    }
+
+    #[test]
+    fn global_average_pooling_2d_keepdims() {
+        let pooling = GlobalPooling::new(PoolingMode::Average, 2, DataFormat::ChannelsLast, true);
+
+        let output = pooling.compute(image_4x4()).unwrap();
+
+        assert_eq!(output.shape(), &[1, 1, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[8.5]);
+    }
+
+    #[test]
+    fn global_max_pooling_1d_channels_first() {
+        let pooling = GlobalPooling::new(PoolingMode::Max, 1, DataFormat::ChannelsFirst, false);
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[1, 2, 3]),
+            vec![1.0, 7.0, 3.0, -4.0, -5.0, -6.0],
+        )
+        .unwrap();
+
+        let output = pooling.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 2]);
+        assert_eq!(output.as_slice().unwrap(), &[7.0, -4.0]);
+    }
+
+    #[test]
+    fn pooling_wrong_rank() {
+        let pooling = Pooling::new(
+            PoolingMode::Max,
+            vec![2, 2],
+            vec![2, 2],
+            Padding::Valid,
+            DataFormat::ChannelsLast,
+        );
+        let global_pooling =
+            GlobalPooling::new(PoolingMode::Max, 2, DataFormat::ChannelsLast, false);
+
+        let error = pooling
+            .compute(NArray::zeros(ndarray::IxDyn(&[4, 4])))
+            .unwrap_err();
+        let global_error = global_pooling
+            .compute(NArray::zeros(ndarray::IxDyn(&[1, 1, 4, 4, 1])))
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            LayerError::IncompatibleInput {
+                layer: "Pooling",
+                ..
+            }
+        ));
+        assert!(matches!(
+            global_error,
+            LayerError::IncompatibleInput {
+                layer: "GlobalPooling",
+                ..
+            }
+        ));
+    }
+}
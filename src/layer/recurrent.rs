@@ -0,0 +1,156 @@
+use crate::layer::{expect_rank, expect_size, ActivationFunction, LayerError, Mask};
+use crate::{Matrix, NArray};
+use ndarray::{ArrayView2, Axis, ErrorKind, Ix2, ShapeError};
+
+/// Options shared by every Keras recurrent layer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecurrentOptions {
+    pub return_sequences: bool,
+    pub return_state: bool,
+    pub go_backwards: bool,
+    /// Outputs zeros on the timesteps the mask marks invalid instead of repeating the previous
+    /// output, as `Bidirectional` does with `return_sequences`.
+    pub zero_output_for_mask: bool,
+}
+
+/// Single timestep computation of a recurrent layer.
+pub trait RecurrentCell {
+    fn units(&self) -> usize;
+
+    /// Number of features of a timestep, the rows of the input kernel.
+    fn features(&self) -> usize;
+
+    /// Number of states carried between the timesteps, the first one is the output.
+    fn state_count(&self) -> usize;
+
+    /// Input part of every gate for all timesteps at once, `[samples, features]` to
+    /// `[samples, gates * units]`.
+    fn project_inputs(&self, inputs: &Matrix) -> Matrix;
+
+    /// Advances `states` by one timestep given the projected inputs of that timestep.
+    fn step(&self, projected: ArrayView2<f32>, states: &mut [Matrix]) -> Result<(), ShapeError>;
+}
+
+/// Static output shape of `cell` run over a `[batch, timesteps, features]` input.
+pub fn cell_output_shape(
+    layer: &'static str,
+    cell: &impl RecurrentCell,
+    options: &RecurrentOptions,
+    input_shape: &[Option<usize>],
+) -> Result<Vec<Option<usize>>, LayerError> {
+    expect_rank(layer, input_shape, 3, "[batch, timesteps, features]")?;
+    expect_size(layer, input_shape, 2, cell.features())?;
+    let units = Some(cell.units());
+    Ok(if options.return_sequences {
+        vec![input_shape[0], input_shape[1], units]
+    } else {
+        vec![input_shape[0], units]
+    })
+}
+
+/// Runs `cell` over a `[batch, timesteps, features]` array, or `[timesteps, features]` for a
+/// single sample. Returns the output followed by the final states when `return_state` is set.
+///
+/// As in Keras, the states are carried over the timesteps `mask` marks invalid, so the output
+/// of such a timestep repeats the previous one, or is zero with `zero_output_for_mask`.
+pub fn run_cell(
+    cell: &impl RecurrentCell,
+    options: &RecurrentOptions,
+    incoming: NArray,
+    mask: Option<&Mask>,
+) -> Result<Vec<NArray>, LayerError> {
+    let unbatched = incoming.ndim() == 2;
+    let (incoming, mask) = if unbatched {
+        (
+            incoming.insert_axis(Axis(0)),
+            mask.map(|mask| mask.clone().insert_axis(Axis(0))),
+        )
+    } else {
+        (incoming, mask.cloned())
+    };
+    let incoming = incoming.into_dimensionality::<ndarray::Ix3>()?;
+    let (batch, timesteps, features) = incoming.dim();
+    let mask = mask
+        .map(|mask| mask.into_dimensionality::<Ix2>())
+        .transpose()?;
+    if mask
+        .as_ref()
+        .is_some_and(|mask| mask.dim() != (batch, timesteps))
+    {
+        return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+    }
+
+    let inputs = incoming
+        .as_standard_layout()
+        .into_owned()
+        .into_shape((batch * timesteps, features))?;
+    let projected = cell.project_inputs(&inputs);
+    let gates = projected.ncols();
+    let projected = projected.into_shape((batch, timesteps, gates))?;
+
+    let mut states = vec![Matrix::zeros((batch, cell.units())); cell.state_count()];
+    let mut output = states[0].clone();
+    let mut sequence = Vec::new();
+    let steps: Box<dyn Iterator<Item = usize>> = if options.go_backwards {
+        Box::new((0..timesteps).rev())
+    } else {
+        Box::new(0..timesteps)
+    };
+    for step in steps {
+        let previous = mask.as_ref().map(|_| states.clone());
+        cell.step(projected.index_axis(Axis(1), step), &mut states)?;
+        output = states[0].clone();
+        if let (Some(mask), Some(previous)) = (&mask, previous) {
+            for (sample, _) in mask
+                .column(step)
+                .iter()
+                .enumerate()
+                .filter(|(_, valid)| !**valid)
+            {
+                for (state, previous) in states.iter_mut().zip(&previous) {
+                    state.row_mut(sample).assign(&previous.row(sample));
+                }
+                let mut row = output.row_mut(sample);
+                if options.zero_output_for_mask {
+                    row.fill(0.0);
+                } else {
+                    row.assign(&states[0].row(sample));
+                }
+            }
+        }
+        if options.return_sequences {
+            sequence.push(output.clone());
+        }
+    }
+
+    let output = if options.return_sequences {
+        let views: Vec<_> = sequence.iter().map(Matrix::view).collect();
+        ndarray::stack(Axis(1), &views)?.into_dyn()
+    } else {
+        output.into_dyn()
+    };
+    let mut outputs = vec![output];
+    if options.return_state {
+        outputs.extend(states.into_iter().map(|state| state.into_dyn()));
+    }
+    if unbatched {
+        outputs = outputs
+            .into_iter()
+            .map(|output| output.remove_axis(Axis(0)))
+            .collect();
+    }
+    Ok(outputs)
+}
+
+/// Columns of the `index`-th gate in a `[batch, gates * units]` matrix packed by Keras.
+pub fn gate(z: &Matrix, index: usize, units: usize) -> Matrix {
+    z.slice(ndarray::s![.., index * units..(index + 1) * units])
+        .to_owned()
+}
+
+/// Applies `activation` to a `[batch, units]` matrix.
+pub fn activate(activation: &ActivationFunction, z: Matrix) -> Result<Matrix, ShapeError> {
+    activation
+        .compute(z.into_dyn())
+        .into_dimensionality::<Ix2>()
+}
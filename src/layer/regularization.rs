@@ -0,0 +1,26 @@
+use crate::layer::{Layer, NdResult};
+use crate::NArray;
+
+/// `Dropout`, `SpatialDropout1D/2D/3D`, `GaussianNoise`, `GaussianDropout`, `AlphaDropout` and
+/// `ActivityRegularization`. They only act while training, so at inference they return their
+/// input unchanged.
+pub struct Regularization;
+
+impl Layer for Regularization {
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(incoming)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regularization_is_identity() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![1.0, -2.0, 3.0, 0.0]).unwrap();
+
+        assert_eq!(Regularization.compute(input.clone()).unwrap(), input);
+    }
+}
@@ -0,0 +1,63 @@
+use crate::layer::{expect_rank, Layer, LayerError, Mask, NdResult};
+use crate::NArray;
+use ndarray::{Axis, ErrorKind, ShapeError};
+
+/// Keras `RepeatVector` layer, turning `[batch, features]` into `[batch, n, features]`.
+pub struct RepeatVector {
+    n: usize,
+}
+
+impl RepeatVector {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+}
+
+impl Layer for RepeatVector {
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        expect_rank("RepeatVector", input_shape, 2, "[batch, features]")?;
+        Ok(vec![input_shape[0], Some(self.n), input_shape[1]])
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        if incoming.ndim() != 2 {
+            return Err(LayerError::incompatible_input(
+                "RepeatVector",
+                incoming.shape(),
+                String::from("the input must be [batch, features]"),
+            ));
+        }
+        let (batch, features) = (incoming.shape()[0], incoming.shape()[1]);
+        let incoming = incoming.insert_axis(Axis(1));
+        let repeated = incoming
+            .broadcast(vec![batch, self.n, features])
+            .ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleShape))?;
+        Ok(repeated.to_owned())
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_vector() {
+        let input =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 2]), vec![1.0, 2.0, 3.0, 4.0]).unwrap();
+
+        let output = RepeatVector::new(3).compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[2, 3, 2]);
+        assert_eq!(
+            output.as_slice().unwrap(),
+            &[1.0, 2.0, 1.0, 2.0, 1.0, 2.0, 3.0, 4.0, 3.0, 4.0, 3.0, 4.0]
+        );
+    }
+}
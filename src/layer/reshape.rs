@@ -0,0 +1,134 @@
+use crate::layer::{Layer, LayerError, Mask, NdResult};
+use crate::NArray;
+
+/// Keras `Reshape` layer. `target_shape` excludes the batch axis and may contain a single
+/// -1, inferred from the number of elements of each sample.
+pub struct Reshape {
+    target_shape: Vec<isize>,
+}
+
+impl Reshape {
+    pub fn new(target_shape: Vec<isize>) -> Self {
+        Self { target_shape }
+    }
+
+    /// Target shape of a sample holding `size` elements, with the -1 resolved.
+    fn resolve(&self, size: usize) -> Result<Vec<usize>, String> {
+        let unknown: Vec<usize> = (0..self.target_shape.len())
+            .filter(|&axis| self.target_shape[axis] < 0)
+            .collect();
+        if unknown.len() > 1 || self.target_shape.iter().any(|&dim| dim < -1) {
+            return Err(format!(
+                "the target shape {:?} may contain a single -1 and no other negative size",
+                self.target_shape
+            ));
+        }
+        let known: usize = self
+            .target_shape
+            .iter()
+            .filter(|&&dim| dim >= 0)
+            .map(|&dim| dim as usize)
+            .product();
+        let mut shape: Vec<usize> = self
+            .target_shape
+            .iter()
+            .map(|&dim| dim.max(0) as usize)
+            .collect();
+        match unknown.first() {
+            Some(&axis) if known != 0 && size.is_multiple_of(known) => shape[axis] = size / known,
+            None if known == size => {}
+            _ => {
+                return Err(format!(
+                    "the {size} elements of each sample can't be reshaped to {:?}",
+                    self.target_shape
+                ))
+            }
+        }
+        Ok(shape)
+    }
+}
+
+impl Layer for Reshape {
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        let Some((&batch, sample)) = input_shape.split_first() else {
+            return Err(LayerError::incompatible_shape(
+                "Reshape",
+                input_shape,
+                String::from("the input has no batch axis"),
+            ));
+        };
+        let size = sample
+            .iter()
+            .try_fold(1, |product, size| size.map(|size| product * size));
+        let target: Vec<Option<usize>> = match size {
+            Some(size) => self
+                .resolve(size)
+                .map_err(|reason| LayerError::incompatible_shape("Reshape", input_shape, reason))?
+                .into_iter()
+                .map(Some)
+                .collect(),
+            None => self
+                .target_shape
+                .iter()
+                .map(|&dim| usize::try_from(dim).ok())
+                .collect(),
+        };
+        Ok([batch].into_iter().chain(target).collect())
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        let Some(&batch) = incoming.shape().first() else {
+            return Err(LayerError::incompatible_input(
+                "Reshape",
+                incoming.shape(),
+                String::from("the input has no batch axis"),
+            ));
+        };
+        let size = incoming.len().checked_div(batch).unwrap_or(0);
+        let target = self.resolve(size).map_err(|reason| {
+            LayerError::incompatible_input("Reshape", incoming.shape(), reason)
+        })?;
+        let shape: Vec<usize> = [batch].into_iter().chain(target).collect();
+        Ok(incoming
+            .as_standard_layout()
+            .into_owned()
+            .into_shape(shape)?)
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, _mask: Option<&Mask>) -> Option<Mask> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input() -> NArray {
+        NArray::from_shape_fn(ndarray::IxDyn(&[2, 6]), |index| {
+            (index[0] * 6 + index[1]) as f32
+        })
+    }
+
+    #[test]
+    fn reshape_infers_unknown_size() {
+        let output = Reshape::new(vec![-1, 2]).compute(input()).unwrap();
+
+        assert_eq!(output.shape(), &[2, 3, 2]);
+        assert_eq!(output[[1, 2, 1]], 11.0);
+    }
+
+    #[test]
+    fn reshape_reports_incompatible_shape() {
+        let error = Reshape::new(vec![-1, 4]).compute(input()).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Reshape can't handle an input of shape [2, 6]: \
+             the 6 elements of each sample can't be reshaped to [-1, 4]"
+        );
+    }
+}
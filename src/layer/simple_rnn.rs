@@ -0,0 +1,144 @@
+use crate::layer::recurrent::{
+    activate, cell_output_shape, run_cell, RecurrentCell, RecurrentOptions,
+};
+use crate::layer::{ActivationFunction, Layer, LayerError, Mask, NdResult, ParamCount};
+use crate::{Matrix, NArray, Vector};
+use ndarray::{ArrayView2, ShapeError};
+
+/// Keras `SimpleRNN` layer, the output is fed back as the only state.
+pub struct SimpleRnn {
+    kernel: Matrix,
+    recurrent_kernel: Matrix,
+    bias: Vector,
+    use_bias: bool,
+    activation: ActivationFunction,
+    options: RecurrentOptions,
+}
+
+impl SimpleRnn {
+    pub fn new(
+        kernel: Matrix,
+        recurrent_kernel: Matrix,
+        bias: Option<Vector>,
+        activation: ActivationFunction,
+        options: RecurrentOptions,
+    ) -> Self {
+        let use_bias = bias.is_some();
+        let bias = bias.unwrap_or_else(|| Vector::zeros(recurrent_kernel.ncols()));
+        Self {
+            kernel,
+            recurrent_kernel,
+            bias,
+            use_bias,
+            activation,
+            options,
+        }
+    }
+}
+
+impl RecurrentCell for SimpleRnn {
+    fn units(&self) -> usize {
+        self.recurrent_kernel.nrows()
+    }
+
+    fn features(&self) -> usize {
+        self.kernel.nrows()
+    }
+
+    fn state_count(&self) -> usize {
+        1
+    }
+
+    fn project_inputs(&self, inputs: &Matrix) -> Matrix {
+        inputs.dot(&self.kernel) + &self.bias
+    }
+
+    fn step(&self, projected: ArrayView2<f32>, states: &mut [Matrix]) -> Result<(), ShapeError> {
+        let z = &projected + &states[0].dot(&self.recurrent_kernel);
+        states[0] = activate(&self.activation, z)?;
+        Ok(())
+    }
+}
+
+impl Layer for SimpleRnn {
+    fn param_count(&self) -> ParamCount {
+        let bias = if self.use_bias { self.bias.len() } else { 0 };
+        ParamCount::trainable(self.kernel.len() + self.recurrent_kernel.len() + bias)
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        Ok(self.compute_all(incoming)?.swap_remove(0))
+    }
+
+    fn compute_all(&self, incoming: NArray) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, None)
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        cell_output_shape("SimpleRNN", self, &self.options, input_shape)
+    }
+
+    fn compute_mask(&self, _incoming: &NArray, mask: Option<&Mask>) -> Option<Mask> {
+        mask.filter(|_| self.options.return_sequences).cloned()
+    }
+
+    fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, LayerError> {
+        run_cell(self, &self.options, incoming, mask)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_approx_eq::assert_approx_eq;
+
+    fn simple_rnn(options: RecurrentOptions) -> SimpleRnn {
+        SimpleRnn::new(
+            Matrix::from_elem((1, 1), 0.5),
+            Matrix::from_elem((1, 1), -0.7),
+            Some(Vector::from_elem(1, 0.1)),
+            ActivationFunction::Tanh,
+            options,
+        )
+    }
+
+    fn sequence() -> NArray {
+        NArray::from_shape_vec(ndarray::IxDyn(&[3, 1]), vec![1.0, 2.0, -1.0]).unwrap()
+    }
+
+    #[test]
+    fn simple_rnn_return_sequences() {
+        let layer = simple_rnn(RecurrentOptions {
+            return_sequences: true,
+            ..Default::default()
+        });
+
+        let output = layer.compute(sequence()).unwrap();
+
+        assert_eq!(output.shape(), &[3, 1]);
+        let expected = [0.5370496, 0.6194211, -0.6824015];
+        for (actual, expected) in output.iter().zip(expected.iter()) {
+            assert_approx_eq!(actual, expected, 1e-6);
+        }
+    }
+
+    #[test]
+    fn simple_rnn_go_backwards() {
+        let layer = simple_rnn(RecurrentOptions {
+            go_backwards: true,
+            ..Default::default()
+        });
+
+        let output = layer.compute(sequence()).unwrap();
+
+        assert_eq!(output.shape(), &[1]);
+        assert_approx_eq!(output[[0]], -0.01443742, 1e-6);
+    }
+}
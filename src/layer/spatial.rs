@@ -0,0 +1,104 @@
+use crate::NArray;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Padding {
+    Valid,
+    Same,
+}
+
+impl Padding {
+    /// Returns the output length along one spatial axis and the padding added before the
+    /// first element. As in TensorFlow, an odd total padding puts the extra element at the end.
+    pub fn output_size(&self, input: usize, window: usize, stride: usize) -> (usize, usize) {
+        match self {
+            Padding::Valid if input < window => (0, 0),
+            Padding::Valid => ((input - window) / stride + 1, 0),
+            Padding::Same => {
+                let output = input.div_ceil(stride);
+                let total = ((output.max(1) - 1) * stride + window).saturating_sub(input);
+                (output, total / 2)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataFormat {
+    #[default]
+    ChannelsLast,
+    ChannelsFirst,
+}
+
+impl DataFormat {
+    /// Axis holding the channels of a batched input of rank `rank`.
+    pub fn channels_axis(&self, rank: usize) -> usize {
+        match self {
+            DataFormat::ChannelsLast => rank.saturating_sub(1),
+            DataFormat::ChannelsFirst => 1,
+        }
+    }
+
+    /// Spatial axes of a batched input of rank `rank`.
+    pub fn spatial_axes(&self, rank: usize) -> std::ops::Range<usize> {
+        match self {
+            DataFormat::ChannelsLast => 1..rank.saturating_sub(1),
+            DataFormat::ChannelsFirst => 2..rank,
+        }
+    }
+
+    /// Moves the channels axis of a batched array to the end.
+    pub fn to_channels_last(&self, incoming: NArray) -> NArray {
+        match self {
+            DataFormat::ChannelsLast => incoming,
+            DataFormat::ChannelsFirst => {
+                let rank = incoming.ndim();
+                let axes: Vec<usize> = [0].into_iter().chain(2..rank).chain([1]).collect();
+                incoming.permuted_axes(axes)
+            }
+        }
+    }
+
+    /// Moves the channels axis of a batched channels-last array back to where it belongs.
+    pub fn from_channels_last(&self, incoming: NArray) -> NArray {
+        match self {
+            DataFormat::ChannelsLast => incoming,
+            DataFormat::ChannelsFirst => {
+                let rank = incoming.ndim();
+                let axes: Vec<usize> = [0, rank - 1].into_iter().chain(1..rank - 1).collect();
+                incoming.permuted_axes(axes)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_padding_output_size() {
+        assert_eq!(Padding::Same.output_size(3, 2, 1), (3, 0));
+        assert_eq!(Padding::Same.output_size(5, 3, 2), (3, 1));
+        assert_eq!(Padding::Same.output_size(4, 3, 2), (2, 0));
+        assert_eq!(Padding::Valid.output_size(5, 3, 2), (2, 0));
+    }
+
+    #[test]
+    fn channels_first_round_trip() {
+        let incoming = NArray::from_shape_fn(ndarray::IxDyn(&[2, 3, 4, 5]), |index| {
+            (index[0] * 1000 + index[1] * 100 + index[2] * 10 + index[3]) as f32
+        });
+
+        let channels_last = DataFormat::ChannelsFirst.to_channels_last(incoming.clone());
+
+        assert_eq!(channels_last.shape(), &[2, 4, 5, 3]);
+        assert_eq!(channels_last[[1, 2, 3, 0]], 1023.0);
+        assert_eq!(
+            DataFormat::ChannelsFirst.from_channels_last(channels_last),
+            incoming
+        );
+    }
+}
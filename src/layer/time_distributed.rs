@@ -0,0 +1,99 @@
+use crate::layer::{Layer, LayerError, NdResult, ParamCount};
+use crate::NArray;
+use ndarray::{ErrorKind, ShapeError};
+
+/// Keras `TimeDistributed` wrapper applying the same layer to every timestep. The batch and
+/// time axes are merged so the wrapped layer runs once over all the timesteps.
+pub struct TimeDistributed {
+    layer: Box<dyn Layer>,
+}
+
+impl TimeDistributed {
+    pub fn new(layer: Box<dyn Layer>) -> Self {
+        Self { layer }
+    }
+}
+
+impl Layer for TimeDistributed {
+    fn param_count(&self) -> ParamCount {
+        self.layer.param_count()
+    }
+
+    fn output_shape(
+        &self,
+        input_shape: &[Option<usize>],
+    ) -> Result<Vec<Option<usize>>, LayerError> {
+        if input_shape.len() < 3 {
+            return Err(LayerError::incompatible_shape(
+                "TimeDistributed",
+                input_shape,
+                String::from("expected [batch, timesteps, ...]"),
+            ));
+        }
+        let merged_batch = input_shape[0]
+            .zip(input_shape[1])
+            .map(|(batch, timesteps)| batch * timesteps);
+        let merged_shape: Vec<Option<usize>> = [merged_batch]
+            .into_iter()
+            .chain(input_shape[2..].iter().copied())
+            .collect();
+        let output_shape = self.layer.output_shape(&merged_shape)?;
+        Ok(input_shape[..2]
+            .iter()
+            .copied()
+            .chain(output_shape.into_iter().skip(1))
+            .collect())
+    }
+
+    fn compute(&self, incoming: NArray) -> NdResult {
+        if incoming.ndim() < 3 {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape).into());
+        }
+        let (batch, timesteps) = (incoming.shape()[0], incoming.shape()[1]);
+        let merged_shape: Vec<usize> = [batch * timesteps]
+            .into_iter()
+            .chain(incoming.shape()[2..].iter().copied())
+            .collect();
+        let merged = incoming
+            .as_standard_layout()
+            .into_owned()
+            .into_shape(merged_shape)?;
+
+        let output = self.layer.compute(merged)?;
+        let output_shape: Vec<usize> = [batch, timesteps]
+            .into_iter()
+            .chain(output.shape()[1..].iter().copied())
+            .collect();
+        Ok(output
+            .as_standard_layout()
+            .into_owned()
+            .into_shape(output_shape)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::Dense;
+    use crate::{Matrix, Vector};
+
+    #[test]
+    fn time_distributed_dense() {
+        let dense = Dense::new(
+            Matrix::from_shape_vec((2, 1), vec![1.0, -1.0]).unwrap(),
+            Some(Vector::from_elem(1, 0.5)),
+            None,
+        );
+        let layer = TimeDistributed::new(Box::new(dense));
+        let input = NArray::from_shape_vec(
+            ndarray::IxDyn(&[1, 3, 2]),
+            vec![1.0, 2.0, 5.0, 3.0, 0.0, 0.0],
+        )
+        .unwrap();
+
+        let output = layer.compute(input).unwrap();
+
+        assert_eq!(output.shape(), &[1, 3, 1]);
+        assert_eq!(output.as_slice().unwrap(), &[-0.5, 2.5, 0.5]);
+    }
+}
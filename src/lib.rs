@@ -2,6 +2,7 @@ pub mod activations;
 pub mod configuration;
 pub mod layer;
 pub mod model;
+pub mod weights;
 
 pub type Vector = ndarray::Array1<f32>;
 pub type Matrix = ndarray::Array2<f32>;
@@ -1,18 +1,14 @@
 use itertools::Itertools;
 use ndarray::{Array1, Array4, Axis, ShapeError};
 use ndarray_npy::NpzReader;
-use rust_deep_learning::configuration::Config;
 use rust_deep_learning::model::sequential::{ModelError, SequentialModel};
-use std::fs::{self, File};
+use std::fs::File;
 use std::time::Instant;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 enum MainError {
-    #[error("Can't open hdf5 file ")]
-    Hdf5OpenError(#[from] hdf5::Error),
-    #[error("Can't parse config error ")]
-    ConfigParseError(#[from] serde_json::Error),
     #[error("Can't build model")]
     ModelBuildError(#[from] ModelError),
     #[error("Can't read file")]
@@ -26,27 +22,23 @@ enum MainError {
 }
 
 fn main() -> Result<(), MainError> {
-    let hdf5_file = hdf5::File::open("model.weights.h5")?;
-    let deserialized: Config = serde_json::from_str(fs::read_to_string("config.json")?.as_str())
-        .map_err(MainError::ConfigParseError)?;
-    let model = SequentialModel::from_config_and_hdf5(deserialized, &hdf5_file)?;
+    let model = SequentialModel::from_keras_file("model.keras")?;
+    if let Some(version) = model.get_keras_version() {
+        println!("Model saved by Keras {}", version);
+    }
     let mut npz = NpzReader::new(File::open("test_data.npz")?)?;
     let x: Array4<f32> = npz.by_name("X.npy")?;
     let y: Array1<i64> = npz.by_name("y.npy")?;
 
-    let mut correct = 0;
     let now = Instant::now();
-    for (index, case) in x.axis_iter(Axis(0)).enumerate() {
-        // 'case' is now a view of a 3D array representing one case
-        let result_arr = model.compute(case.to_owned().into_dyn())?;
-        let arr_len = result_arr.len();
-        let result_1d_array = result_arr.into_shape(arr_len)?;
-
-        let argmax_index = result_1d_array
+    let predictions = model.predict_batch(x.into_dyn())?;
+    let mut correct = 0;
+    for (prediction, expected) in predictions.axis_iter(Axis(0)).zip(y.iter()) {
+        let argmax_index = prediction
             .iter()
             .position_max_by(|x, y| x.total_cmp(y))
             .ok_or(MainError::MappingError("Failed to find argmax index"))?;
-        if argmax_index as i64 == y[index] {
+        if argmax_index as i64 == *expected {
             correct += 1;
         }
     }
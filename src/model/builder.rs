@@ -0,0 +1,621 @@
+use crate::configuration::layers::{
+    ActivationConfig, BatchNormalizationConfig, BidirectionalConfig, ConcatenateConfig,
+    Conv2DConfig, DenseConfig, DotConfig, EluConfig, EmbeddingConfig, FlattenConfig,
+    GlobalPoolingConfig, GroupNormalizationConfig, LayerNormalizationConfig, LeakyReluConfig,
+    MultiHeadAttentionConfig, PReluConfig, PermuteConfig, PoolingConfig, RecurrentConfig,
+    ReluConfig, RepeatVectorConfig, ReshapeConfig, SoftmaxConfig, TimeDistributedConfig,
+};
+use crate::configuration::{self, LayerType, TensorReference};
+use crate::layer::{
+    Activation, ActivationFunction, AttentionInput, AttentionOptions, BatchNormalization,
+    Bidirectional, Concatenate, Conv2D, Dense, Dot, Elu, Embedding, Flatten, GlobalPooling,
+    GroupNormalization, Gru, Layer, LayerNormalization, LeakyRelu, Lstm, Merge, MergeOperation,
+    MultiHeadAttention, PRelu, Permute, Pooling, PoolingMode, RecurrentOptions, Regularization,
+    Relu, RepeatVector, Reshape, SimpleRnn, Softmax, TimeDistributed,
+};
+use crate::model::sequential::ModelError;
+use crate::weights::LayerWeights;
+use crate::{Matrix, NArray, Vector};
+use ndarray::Ix4;
+use serde_json::Value;
+
+/// Where the variables of a layer are read from. They are only looked up by layers that have
+/// some, as Keras does not always store a group for the other ones.
+enum WeightsSource<'a> {
+    /// The group named after the layer in the weights file.
+    File(&'a hdf5::File),
+    /// The group a wrapper found for the layer it holds.
+    Wrapped(LayerWeights),
+}
+
+impl WeightsSource<'_> {
+    fn open(&self, layer_config: &configuration::Layer) -> Result<LayerWeights, ModelError> {
+        match self {
+            WeightsSource::File(file) => {
+                let name = layer_name(layer_config)?;
+                // The group is looked up under several roots, so only its name is known.
+                LayerWeights::from_file(file, name).map_err(|error| ModelError::WeightsError {
+                    path: name.to_owned(),
+                    error,
+                })
+            }
+            WeightsSource::Wrapped(weights) => Ok(weights.clone()),
+        }
+    }
+}
+
+/// Creates the layer described by `layer_config`, `None` for layers without computation.
+pub fn build_layer(
+    layer_config: &configuration::Layer,
+    file: &hdf5::File,
+) -> Result<Option<Box<dyn Layer>>, ModelError> {
+    build(layer_config, &WeightsSource::File(file))
+}
+
+fn build(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Option<Box<dyn Layer>>, ModelError> {
+    let layer: Box<dyn Layer> = match layer_config.get_class_name() {
+        LayerType::Activation => {
+            let config: ActivationConfig = layer_config.parse_config()?;
+            Box::new(Activation::new(config.activation))
+        }
+        LayerType::Relu => {
+            let config: ReluConfig = layer_config.parse_config()?;
+            Box::new(Relu::new(
+                config.max_value,
+                config.negative_slope,
+                config.threshold,
+            ))
+        }
+        LayerType::LeakyRelu => {
+            let config: LeakyReluConfig = layer_config.parse_config()?;
+            Box::new(LeakyRelu::new(config.get_negative_slope()))
+        }
+        LayerType::PRelu => {
+            let config: PReluConfig = layer_config.parse_config()?;
+            let weights = source.open(layer_config)?;
+            let alpha: NArray = read_variable(&weights, 0, "alpha", |dataset| dataset.read_dyn())?;
+            // alpha has a size of 1 on the shared axes, counted from the batch axis.
+            let shared = config.shared_axes.unwrap_or_default();
+            if shared.iter().any(|&axis| {
+                usize::try_from(axis - 1).map_or(true, |axis| alpha.shape().get(axis) != Some(&1))
+            }) {
+                return Err(ModelError::ConfigurationError(
+                    "PReLU alpha isn't shared along its shared_axes",
+                ));
+            }
+            Box::new(PRelu::new(alpha))
+        }
+        LayerType::Elu => {
+            let config: EluConfig = layer_config.parse_config()?;
+            Box::new(Elu::new(config.alpha.unwrap_or(1.0)))
+        }
+        LayerType::Softmax => {
+            let config: SoftmaxConfig = layer_config.parse_config()?;
+            let axis = config.axis.single().ok_or(ModelError::ConfigurationError(
+                "Softmax over several axes is not supported",
+            ))?;
+            Box::new(Softmax::new(axis))
+        }
+        LayerType::Add => Box::new(Merge::new(MergeOperation::Add)),
+        LayerType::Subtract => Box::new(Merge::new(MergeOperation::Subtract)),
+        LayerType::Multiply => Box::new(Merge::new(MergeOperation::Multiply)),
+        LayerType::Average => Box::new(Merge::new(MergeOperation::Average)),
+        LayerType::Maximum => Box::new(Merge::new(MergeOperation::Maximum)),
+        LayerType::Minimum => Box::new(Merge::new(MergeOperation::Minimum)),
+        LayerType::Concatenate => {
+            let config: ConcatenateConfig = layer_config.parse_config()?;
+            Box::new(Concatenate::new(config.axis))
+        }
+        LayerType::Dot => Box::new(build_dot(layer_config)?),
+        LayerType::BatchNormalization => Box::new(build_batch_normalization(layer_config, source)?),
+        LayerType::Bidirectional => Box::new(build_bidirectional(layer_config, source)?),
+        LayerType::Conv2D => Box::new(build_conv2d(layer_config, source)?),
+        LayerType::Dense => Box::new(build_dense(layer_config, source)?),
+        LayerType::Embedding => {
+            let config: EmbeddingConfig = layer_config.parse_config()?;
+            let weights = source.open(layer_config)?;
+            let embeddings = read_variable(&weights, 0, "embeddings", |dataset| dataset.read_2d())?;
+            Box::new(Embedding::new(embeddings, config.mask_zero))
+        }
+        LayerType::Flatten => {
+            let config: FlattenConfig = layer_config.parse_config()?;
+            Box::new(Flatten::new(config.data_format.unwrap_or_default()))
+        }
+        LayerType::MaxPooling1D | LayerType::MaxPooling2D => {
+            Box::new(build_pooling(layer_config, PoolingMode::Max)?)
+        }
+        LayerType::AveragePooling1D | LayerType::AveragePooling2D => {
+            Box::new(build_pooling(layer_config, PoolingMode::Average)?)
+        }
+        LayerType::GlobalMaxPooling1D => {
+            Box::new(build_global_pooling(layer_config, PoolingMode::Max, 1)?)
+        }
+        LayerType::GlobalMaxPooling2D => {
+            Box::new(build_global_pooling(layer_config, PoolingMode::Max, 2)?)
+        }
+        LayerType::GlobalAveragePooling1D => {
+            Box::new(build_global_pooling(layer_config, PoolingMode::Average, 1)?)
+        }
+        LayerType::GlobalAveragePooling2D => {
+            Box::new(build_global_pooling(layer_config, PoolingMode::Average, 2)?)
+        }
+        LayerType::GroupNormalization => {
+            let config: GroupNormalizationConfig = layer_config.parse_config()?;
+            let (gamma, beta) =
+                build_normalization_parameters(layer_config, source, config.scale, config.center)?;
+            Box::new(GroupNormalization::new(
+                config.groups,
+                config.axis,
+                config.epsilon,
+                gamma,
+                beta,
+            ))
+        }
+        LayerType::InputLayer => return Ok(None),
+        LayerType::LayerNormalization => {
+            let config: LayerNormalizationConfig = layer_config.parse_config()?;
+            if config.rms_scaling {
+                return Err(ModelError::ConfigurationError(
+                    "LayerNormalization with rms_scaling is not supported",
+                ));
+            }
+            let (gamma, beta) =
+                build_normalization_parameters(layer_config, source, config.scale, config.center)?;
+            Box::new(LayerNormalization::new(
+                config.axis.to_vec(),
+                config.epsilon,
+                gamma,
+                beta,
+            ))
+        }
+        LayerType::Dropout
+        | LayerType::SpatialDropout1D
+        | LayerType::SpatialDropout2D
+        | LayerType::SpatialDropout3D
+        | LayerType::GaussianNoise
+        | LayerType::GaussianDropout
+        | LayerType::AlphaDropout
+        | LayerType::ActivityRegularization => Box::new(Regularization),
+        LayerType::Lstm => Box::new(build_lstm(layer_config, source)?),
+        LayerType::MultiHeadAttention => {
+            Box::new(build_multi_head_attention(layer_config, source)?)
+        }
+        LayerType::Permute => {
+            let config: PermuteConfig = layer_config.parse_config()?;
+            Box::new(Permute::new(config.dims))
+        }
+        LayerType::RepeatVector => {
+            let config: RepeatVectorConfig = layer_config.parse_config()?;
+            Box::new(RepeatVector::new(config.n))
+        }
+        LayerType::Reshape => {
+            let config: ReshapeConfig = layer_config.parse_config()?;
+            Box::new(Reshape::new(config.target_shape))
+        }
+        LayerType::Gru => Box::new(build_gru(layer_config, source)?),
+        LayerType::SimpleRnn => Box::new(build_simple_rnn(layer_config, source)?),
+        LayerType::TimeDistributed => {
+            let config: TimeDistributedConfig = layer_config.parse_config()?;
+            let weights = wrapped(&source.open(layer_config)?, "layer", "")?;
+            Box::new(TimeDistributed::new(build_wrapped(config.layer, weights)?))
+        }
+    };
+    Ok(Some(layer))
+}
+
+fn layer_name(layer_config: &configuration::Layer) -> Result<&str, ModelError> {
+    layer_config
+        .get_name()
+        .ok_or(ModelError::ConfigurationError("Failed to find layer name"))
+}
+
+/// Reads the variable at `index` or called `name` of `weights`, naming its dataset on failure.
+fn read_variable<T>(
+    weights: &LayerWeights,
+    index: usize,
+    name: &str,
+    read: impl FnOnce(&hdf5::Dataset) -> hdf5::Result<T>,
+) -> Result<T, ModelError> {
+    let dataset = weights
+        .variable(index, name)
+        .map_err(|error| ModelError::WeightsError {
+            path: weights.variable_path(index, name),
+            error,
+        })?;
+    read(&dataset).map_err(|error| ModelError::WeightsError {
+        path: dataset.name(),
+        error,
+    })
+}
+
+fn sublayer(weights: &LayerWeights, name: &str) -> Result<LayerWeights, ModelError> {
+    weights
+        .sublayer(name)
+        .map_err(|error| ModelError::WeightsError {
+            path: format!("{}/{name}", weights.get_path()),
+            error,
+        })
+}
+
+fn wrapped(
+    weights: &LayerWeights,
+    attribute: &str,
+    legacy_prefix: &str,
+) -> Result<LayerWeights, ModelError> {
+    weights
+        .wrapped(attribute, legacy_prefix)
+        .map_err(|error| ModelError::WeightsError {
+            path: format!("{}/{attribute}", weights.get_path()),
+            error,
+        })
+}
+
+fn build_dense(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Dense, ModelError> {
+    let config: DenseConfig = layer_config.parse_config()?;
+    let weights = source.open(layer_config)?;
+    let kernel: Matrix = read_variable(&weights, 0, "kernel", |dataset| dataset.read_2d())?;
+    let bias = config
+        .use_bias
+        .then(|| read_variable(&weights, 1, "bias", |dataset| dataset.read_1d()))
+        .transpose()?;
+    Ok(Dense::new(kernel, bias, config.activation))
+}
+
+fn build_conv2d(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Conv2D, ModelError> {
+    let config: Conv2DConfig = layer_config.parse_config()?;
+    if config.groups.is_some_and(|groups| groups != 1) {
+        return Err(ModelError::ConfigurationError(
+            "Grouped Conv2D is not supported",
+        ));
+    }
+    let weights = source.open(layer_config)?;
+    let kernel = read_variable(&weights, 0, "kernel", |dataset| dataset.read::<f32, Ix4>())?;
+    let bias = config
+        .use_bias
+        .then(|| read_variable(&weights, 1, "bias", |dataset| dataset.read_1d()))
+        .transpose()?;
+    let (height, width, _, filters) = kernel.dim();
+    if [height, width] != config.kernel_size
+        || filters != config.filters
+        || bias.as_ref().is_some_and(|bias| bias.len() != filters)
+    {
+        return Err(ModelError::ConfigurationError(
+            "Conv2D weights don't match its filters and kernel_size",
+        ));
+    }
+    Ok(Conv2D::new(
+        kernel,
+        bias,
+        config.strides,
+        config.padding,
+        config.dilation_rate,
+        config.data_format,
+        config.activation,
+    ))
+}
+
+fn build_pooling(
+    layer_config: &configuration::Layer,
+    mode: PoolingMode,
+) -> Result<Pooling, ModelError> {
+    let config: PoolingConfig = layer_config.parse_config()?;
+    let strides = config.strides.unwrap_or_else(|| config.pool_size.clone());
+    Ok(Pooling::new(
+        mode,
+        config.pool_size,
+        strides,
+        config.padding,
+        config.data_format,
+    ))
+}
+
+fn build_global_pooling(
+    layer_config: &configuration::Layer,
+    mode: PoolingMode,
+    spatial_dims: usize,
+) -> Result<GlobalPooling, ModelError> {
+    let config: GlobalPoolingConfig = layer_config.parse_config()?;
+    Ok(GlobalPooling::new(
+        mode,
+        spatial_dims,
+        config.data_format,
+        config.keepdims,
+    ))
+}
+
+fn recurrent_options(config: &RecurrentConfig) -> RecurrentOptions {
+    RecurrentOptions {
+        return_sequences: config.return_sequences,
+        return_state: config.return_state,
+        go_backwards: config.go_backwards,
+        zero_output_for_mask: config.zero_output_for_mask,
+    }
+}
+
+/// The `recurrent_activation` that `LSTM` and `GRU` require.
+fn recurrent_activation(config: &RecurrentConfig) -> Result<ActivationFunction, ModelError> {
+    config
+        .recurrent_activation
+        .clone()
+        .ok_or(ModelError::ConfigurationError(
+            "Failed to find recurrent_activation",
+        ))
+}
+
+fn build_lstm(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Lstm, ModelError> {
+    let config: RecurrentConfig = layer_config.parse_config()?;
+    let weights = sublayer(&source.open(layer_config)?, "cell")?;
+    let kernel: Matrix = read_variable(&weights, 0, "kernel", |dataset| dataset.read_2d())?;
+    let recurrent_kernel: Matrix =
+        read_variable(&weights, 1, "recurrent_kernel", |dataset| dataset.read_2d())?;
+    let bias = config
+        .use_bias
+        .then(|| read_variable(&weights, 2, "bias", |dataset| dataset.read_1d()))
+        .transpose()?;
+    Ok(Lstm::new(
+        kernel,
+        recurrent_kernel,
+        bias,
+        config.activation.clone(),
+        recurrent_activation(&config)?,
+        recurrent_options(&config),
+    ))
+}
+
+fn build_gru(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Gru, ModelError> {
+    let config: RecurrentConfig = layer_config.parse_config()?;
+    let weights = sublayer(&source.open(layer_config)?, "cell")?;
+    let kernel: Matrix = read_variable(&weights, 0, "kernel", |dataset| dataset.read_2d())?;
+    let recurrent_kernel: Matrix =
+        read_variable(&weights, 1, "recurrent_kernel", |dataset| dataset.read_2d())?;
+    // Keras 3 and recent Keras 2 versions reset after the matrix multiplication by default.
+    let reset_after = config.reset_after.unwrap_or(true);
+    let bias = if !config.use_bias {
+        None
+    } else if reset_after {
+        Some(read_variable(&weights, 2, "bias", |dataset| {
+            dataset.read_2d()
+        })?)
+    } else {
+        Some(
+            read_variable(&weights, 2, "bias", |dataset| dataset.read_1d())?
+                .insert_axis(ndarray::Axis(0)),
+        )
+    };
+    Ok(Gru::new(
+        kernel,
+        recurrent_kernel,
+        bias,
+        reset_after,
+        config.activation.clone(),
+        recurrent_activation(&config)?,
+        recurrent_options(&config),
+    ))
+}
+
+fn build_simple_rnn(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<SimpleRnn, ModelError> {
+    let config: RecurrentConfig = layer_config.parse_config()?;
+    let weights = sublayer(&source.open(layer_config)?, "cell")?;
+    let kernel: Matrix = read_variable(&weights, 0, "kernel", |dataset| dataset.read_2d())?;
+    let recurrent_kernel: Matrix =
+        read_variable(&weights, 1, "recurrent_kernel", |dataset| dataset.read_2d())?;
+    let bias = config
+        .use_bias
+        .then(|| read_variable(&weights, 2, "bias", |dataset| dataset.read_1d()))
+        .transpose()?;
+    Ok(SimpleRnn::new(
+        kernel,
+        recurrent_kernel,
+        bias,
+        config.activation.clone(),
+        recurrent_options(&config),
+    ))
+}
+
+/// Builds the layer nested in the `config` of a wrapper, reading its variables from `weights`.
+fn build_wrapped(config: Value, weights: LayerWeights) -> Result<Box<dyn Layer>, ModelError> {
+    let layer_config: configuration::Layer = serde_json::from_value(config)?;
+    build(&layer_config, &WeightsSource::Wrapped(weights))?.ok_or(ModelError::ConfigurationError(
+        "Wrapped layer must compute something",
+    ))
+}
+
+fn build_bidirectional(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<Bidirectional, ModelError> {
+    let config: BidirectionalConfig = layer_config.parse_config()?;
+    let weights = source.open(layer_config)?;
+    let mut forward = config.layer;
+    // Without an explicit backward layer Keras copies the forward one, reversing its direction.
+    let mut backward = match config.backward_layer {
+        Some(backward) => backward,
+        None => {
+            let mut backward = forward.clone();
+            let go_backwards = &mut backward["config"]["go_backwards"];
+            *go_backwards = Value::Bool(!go_backwards.as_bool().unwrap_or(false));
+            backward
+        }
+    };
+    let return_sequences = forward["config"]["return_sequences"]
+        .as_bool()
+        .unwrap_or(false);
+    // Like Keras, both directions output zeros on masked timesteps when returning sequences.
+    for layer in [&mut forward, &mut backward] {
+        layer["config"]["zero_output_for_mask"] = Value::Bool(return_sequences);
+    }
+    Ok(Bidirectional::new(
+        build_wrapped(forward, wrapped(&weights, "forward_layer", "forward_")?)?,
+        build_wrapped(backward, wrapped(&weights, "backward_layer", "backward_")?)?,
+        config.merge_mode,
+        return_sequences,
+    ))
+}
+
+fn build_multi_head_attention(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<MultiHeadAttention, ModelError> {
+    let config: MultiHeadAttentionConfig = layer_config.parse_config()?;
+    if config.attention_axes.is_some() {
+        return Err(ModelError::ConfigurationError(
+            "MultiHeadAttention over custom attention_axes is not supported",
+        ));
+    }
+    let weights = source.open(layer_config)?;
+    // The einsum kernels are [features, heads, dim] for the projections and
+    // [heads, dim, features] for the output, flattened here into Dense kernels.
+    let dense = |attribute: &str, legacy_name: &str, input_axes: usize| {
+        let weights = weights
+            .wrapped(attribute, legacy_name)
+            .or_else(|_| wrapped(&weights, &format!("_{attribute}"), legacy_name))?;
+        let kernel = read_variable(&weights, 0, "kernel", |dataset| dataset.read_dyn::<f32>())?;
+        let units: usize = kernel.shape()[input_axes..].iter().product();
+        let rows = kernel.len() / units;
+        let kernel: Matrix = kernel.into_shape((rows, units))?;
+        let bias = config
+            .use_bias
+            .then(|| read_variable(&weights, 1, "bias", |dataset| dataset.read_raw()))
+            .transpose()?;
+        Ok::<_, ModelError>(Dense::new(kernel, bias.map(Vector::from_vec), None))
+    };
+    Ok(MultiHeadAttention::new(
+        config.num_heads,
+        dense("query_dense", "query", 1)?,
+        dense("key_dense", "key", 1)?,
+        dense("value_dense", "value", 1)?,
+        dense("output_dense", "attention_output", 2)?,
+        attention_options(layer_config)?,
+    ))
+}
+
+/// Reads which tensors the first call of a `MultiHeadAttention` layer receives and the flags
+/// it is called with.
+fn attention_options(layer_config: &configuration::Layer) -> Result<AttentionOptions, ModelError> {
+    let keywords = layer_config
+        .get_call_keywords()
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    let flag = |name: &str| keywords.get(name).and_then(Value::as_bool).unwrap_or(false);
+    let mut options = AttentionOptions {
+        use_causal_mask: flag("use_causal_mask"),
+        return_attention_scores: flag("return_attention_scores"),
+        ..Default::default()
+    };
+    let Some(input_count) = layer_config.get_inbound_nodes().first().map(Vec::len) else {
+        return Ok(options);
+    };
+    let mut keyword_inputs = Vec::new();
+    for (keyword, argument) in &keywords {
+        if TensorReference::collect(argument).is_empty() {
+            continue;
+        }
+        keyword_inputs.push(AttentionInput::from_keyword(keyword).ok_or(
+            ModelError::ConfigurationError("Unsupported tensor argument of MultiHeadAttention"),
+        )?);
+    }
+    let positional = input_count.saturating_sub(keyword_inputs.len());
+    options.inputs = AttentionInput::POSITIONAL
+        .into_iter()
+        .take(positional)
+        .chain(keyword_inputs)
+        .collect();
+    Ok(options)
+}
+
+fn build_batch_normalization(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+) -> Result<BatchNormalization, ModelError> {
+    let config: BatchNormalizationConfig = layer_config.parse_config()?;
+    // Keras 2 stores the axis as a list once the layer is built.
+    let axis = config.axis.single().ok_or(ModelError::ConfigurationError(
+        "BatchNormalization over several axes is not supported",
+    ))?;
+    let weights = source.open(layer_config)?;
+    // Keras only creates gamma and beta when scaling and centering, shifting the other variables.
+    let mut index = 0;
+    let mut optional = |enabled: bool, name: &str| -> Result<Option<Vector>, ModelError> {
+        if !enabled {
+            return Ok(None);
+        }
+        index += 1;
+        Ok(Some(read_variable(&weights, index - 1, name, |dataset| {
+            dataset.read_1d()
+        })?))
+    };
+    let gamma = optional(config.scale, "gamma")?;
+    let beta = optional(config.center, "beta")?;
+    let moving_mean: Vector =
+        read_variable(&weights, index, "moving_mean", |dataset| dataset.read_1d())?;
+    let moving_variance: Vector =
+        read_variable(&weights, index + 1, "moving_variance", |dataset| {
+            dataset.read_1d()
+        })?;
+    Ok(BatchNormalization::new(
+        axis,
+        layer_config.get_input_shape().map(|shape| shape.len()),
+        gamma,
+        beta,
+        moving_mean,
+        moving_variance,
+        config.epsilon,
+    ))
+}
+
+/// `axes` of a `Dot` layer is either shared by both inputs or given for each of them.
+fn build_dot(layer_config: &configuration::Layer) -> Result<Dot, ModelError> {
+    let config: DotConfig = layer_config.parse_config()?;
+    let axes = match *config.axes.to_vec().as_slice() {
+        [axis] => [axis; 2],
+        [first, second] => [first, second],
+        _ => return Err(ModelError::ConfigurationError("Dot takes one or two axes")),
+    };
+    Ok(Dot::new(axes, config.normalize))
+}
+
+/// Reads the `gamma` and `beta` of `LayerNormalization` and `GroupNormalization`, which only
+/// exist when the layer was told to `scale` and `center`.
+fn build_normalization_parameters(
+    layer_config: &configuration::Layer,
+    source: &WeightsSource,
+    scale: bool,
+    center: bool,
+) -> Result<(Option<Vector>, Option<Vector>), ModelError> {
+    if !scale && !center {
+        return Ok((None, None));
+    }
+    let weights = source.open(layer_config)?;
+    let read = |index: usize, name: &str| -> Result<Vector, ModelError> {
+        Ok(Vector::from_vec(read_variable(
+            &weights,
+            index,
+            name,
+            |dataset| dataset.read_raw(),
+        )?))
+    };
+    let gamma = scale.then(|| read(0, "gamma")).transpose()?;
+    let beta = center
+        .then(|| read(usize::from(scale), "beta"))
+        .transpose()?;
+    Ok((gamma, beta))
+}
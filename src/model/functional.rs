@@ -0,0 +1,624 @@
+use crate::configuration::{Config, Metadata, TensorReference};
+use crate::layer::Mask;
+use crate::model::saved_model::SavedModel;
+use crate::model::sequential::{ModelError, ModelLayer};
+use crate::NArray;
+use ndarray::Axis;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use std::path::Path;
+
+/// Single call of a layer inside the graph.
+struct Node {
+    layer_index: usize,
+    layer_name: String,
+    node_index: usize,
+    inputs: Vec<TensorReference>,
+}
+
+type NodeKey<'a> = (&'a str, usize);
+
+fn node_key(reference: &TensorReference) -> NodeKey<'_> {
+    (reference.get_layer_name(), reference.get_node_index())
+}
+
+/// Model built with the Keras functional API, executed as a graph of layer calls.
+pub struct FunctionalModel {
+    layers: Vec<ModelLayer>,
+    // Topologically ordered, every node comes after the nodes producing its inputs.
+    nodes: Vec<Node>,
+    inputs: Vec<TensorReference>,
+    outputs: Vec<TensorReference>,
+    metadata: Option<Metadata>,
+}
+
+impl FunctionalModel {
+    pub fn from_config_and_hdf5(config: Config, file: &hdf5::File) -> Result<Self, ModelError> {
+        Self::build(&config, file)
+    }
+
+    /// Loads the model from a `.keras` archive without unpacking it manually.
+    pub fn from_keras_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_keras_file(path)?)
+    }
+
+    pub fn from_keras_reader<R: Read + Seek>(reader: R) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_keras_reader(reader)?)
+    }
+
+    /// Loads a model saved by Keras 2 / tf.keras with `model.save("model.h5")`.
+    pub fn from_h5_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_h5_file(path)?)
+    }
+
+    fn load(saved_model: SavedModel) -> Result<Self, ModelError> {
+        let mut model = Self::build(saved_model.get_config(), saved_model.get_weights())?;
+        model.metadata = saved_model.into_metadata();
+        Ok(model)
+    }
+
+    fn build(config: &Config, file: &hdf5::File) -> Result<Self, ModelError> {
+        let inputs = config.get_input_layers();
+        let mut outputs = config.get_output_layers();
+        if inputs.is_empty() || outputs.is_empty() {
+            return Err(ModelError::ConfigurationError(
+                "Failed to find input_layers or output_layers",
+            ));
+        }
+
+        let mut layers = Vec::new();
+        let mut nodes = Vec::new();
+        for (index, layer_config) in config.get_layers().iter().enumerate() {
+            let Some(layer) = ModelLayer::build(index, layer_config, file)? else {
+                continue;
+            };
+            let layer_name = layer_config
+                .get_name()
+                .ok_or(ModelError::ConfigurationError("Failed to find layer name"))?;
+            for (node_index, node_inputs) in
+                layer_config.get_inbound_nodes().into_iter().enumerate()
+            {
+                if node_inputs.is_empty() {
+                    return Err(ModelError::ConfigurationError(
+                        "Failed to find the inputs of a layer call",
+                    ));
+                }
+                nodes.push(Node {
+                    layer_index: layers.len(),
+                    layer_name: layer_name.to_owned(),
+                    node_index,
+                    inputs: node_inputs,
+                });
+            }
+            layers.push(layer);
+        }
+
+        let nodes =
+            fold_batch_normalization(&mut layers, sort_nodes(nodes, &inputs)?, &mut outputs);
+        let produced: HashSet<NodeKey> = inputs
+            .iter()
+            .map(node_key)
+            .chain(
+                nodes
+                    .iter()
+                    .map(|node| (node.layer_name.as_str(), node.node_index)),
+            )
+            .collect();
+        if !outputs
+            .iter()
+            .all(|output| produced.contains(&node_key(output)))
+        {
+            return Err(ModelError::ConfigurationError(
+                "Model output isn't produced by any layer",
+            ));
+        }
+
+        Ok(FunctionalModel {
+            layers,
+            nodes,
+            inputs,
+            outputs,
+            metadata: None,
+        })
+    }
+
+    /// Metadata of the `.keras` archive or `.h5` file the model was loaded from, if any.
+    pub fn get_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Version of Keras that saved the model, unknown when built from a bare weights file.
+    pub fn get_keras_version(&self) -> Option<&str> {
+        self.metadata.as_ref().map(Metadata::get_keras_version)
+    }
+
+    /// Names of the input layers, in the order expected by [`FunctionalModel::compute`].
+    pub fn get_input_names(&self) -> Vec<&str> {
+        self.inputs
+            .iter()
+            .map(TensorReference::get_layer_name)
+            .collect()
+    }
+
+    /// Names of the output layers, in the order returned by [`FunctionalModel::compute`].
+    pub fn get_output_names(&self) -> Vec<&str> {
+        self.outputs
+            .iter()
+            .map(TensorReference::get_layer_name)
+            .collect()
+    }
+
+    /// Runs a single sample, every input and output given without the batch axis.
+    pub fn compute(&self, inputs: Vec<NArray>) -> Result<Vec<NArray>, ModelError> {
+        let inputs = inputs
+            .into_iter()
+            .map(|input| input.insert_axis(Axis(0)))
+            .collect();
+        Ok(self
+            .predict_batch(inputs)?
+            .into_iter()
+            .map(|output| output.remove_axis(Axis(0)))
+            .collect())
+    }
+
+    /// Runs `[batch, ...]` inputs through the layer graph, returning `[batch, ...]` outputs.
+    pub fn predict_batch(&self, inputs: Vec<NArray>) -> Result<Vec<NArray>, ModelError> {
+        if inputs.len() != self.inputs.len() {
+            return Err(ModelError::InputCountMismatch {
+                expected: self.inputs.len(),
+                actual: inputs.len(),
+            });
+        }
+        let mut tensors: HashMap<NodeKey, Vec<NArray>> = self
+            .inputs
+            .iter()
+            .map(node_key)
+            .zip(inputs.into_iter().map(|input| vec![input]))
+            .collect();
+
+        // Masks of the first output of the nodes producing one.
+        let mut masks: HashMap<NodeKey, Mask> = HashMap::new();
+
+        for node in &self.nodes {
+            let layer = &self.layers[node.layer_index];
+            let key = (node.layer_name.as_str(), node.node_index);
+            if node.inputs.len() > 1 {
+                // Masks stop at layers taking several inputs.
+                let inputs = node
+                    .inputs
+                    .iter()
+                    .map(|input| lookup(&tensors, input).cloned())
+                    .collect::<Result<Vec<_>, _>>()?;
+                tensors.insert(key, layer.compute_multiple(inputs)?);
+                continue;
+            }
+            let input = lookup(&tensors, &node.inputs[0])?.clone();
+            let mask = Some(&node.inputs[0])
+                .filter(|input| input.get_tensor_index() == 0)
+                .and_then(|input| masks.get(&node_key(input)));
+            let output_mask = layer.layer.compute_mask(&input, mask);
+            let outputs = layer.compute_all_masked(input, mask)?;
+            tensors.insert(key, outputs);
+            if let Some(output_mask) = output_mask {
+                masks.insert(key, output_mask);
+            }
+        }
+
+        self.outputs
+            .iter()
+            .map(|output| lookup(&tensors, output).cloned())
+            .collect()
+    }
+
+    /// Same as [`FunctionalModel::compute`] with inputs and outputs keyed by layer name.
+    pub fn compute_named(
+        &self,
+        mut inputs: HashMap<String, NArray>,
+    ) -> Result<HashMap<String, NArray>, ModelError> {
+        let ordered_inputs = self
+            .get_input_names()
+            .into_iter()
+            .map(|name| {
+                inputs
+                    .remove(name)
+                    .ok_or_else(|| ModelError::MissingInput(name.to_owned()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs = self.compute(ordered_inputs)?;
+        Ok(self
+            .get_output_names()
+            .into_iter()
+            .map(String::from)
+            .zip(outputs)
+            .collect())
+    }
+}
+
+fn lookup<'a>(
+    tensors: &'a HashMap<NodeKey<'a>, Vec<NArray>>,
+    reference: &'a TensorReference,
+) -> Result<&'a NArray, ModelError> {
+    tensors
+        .get(&node_key(reference))
+        .and_then(|outputs| outputs.get(reference.get_tensor_index()))
+        .ok_or(ModelError::ConfigurationError(
+            "Failed to find layer output",
+        ))
+}
+
+/// Orders the nodes so that each one runs once all of its inputs are computed.
+fn sort_nodes(mut pending: Vec<Node>, inputs: &[TensorReference]) -> Result<Vec<Node>, ModelError> {
+    let mut available: HashSet<(String, usize)> = inputs
+        .iter()
+        .map(|input| (input.get_layer_name().to_owned(), input.get_node_index()))
+        .collect();
+    let mut sorted = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let (ready, blocked): (Vec<Node>, Vec<Node>) = pending.into_iter().partition(|node| {
+            node.inputs.iter().all(|input| {
+                available.contains(&(input.get_layer_name().to_owned(), input.get_node_index()))
+            })
+        });
+        if ready.is_empty() {
+            return Err(ModelError::ConfigurationError(
+                "Layer graph contains a cycle or an unknown input",
+            ));
+        }
+        available.extend(
+            ready
+                .iter()
+                .map(|node| (node.layer_name.clone(), node.node_index)),
+        );
+        sorted.extend(ready);
+        pending = blocked;
+    }
+    Ok(sorted)
+}
+
+fn owned_key(reference: &TensorReference) -> (String, usize) {
+    (
+        reference.get_layer_name().to_owned(),
+        reference.get_node_index(),
+    )
+}
+
+/// Folds each `BatchNormalization` into the layer computing its input, as sequential models
+/// do, when both layers are called once and nothing else reads that input. The references to
+/// the normalized tensor then point at the output of the folded layer. `nodes` must be
+/// sorted, the folded `BatchNormalization` layers stay in `layers` without any node.
+fn fold_batch_normalization(
+    layers: &mut [ModelLayer],
+    nodes: Vec<Node>,
+    outputs: &mut [TensorReference],
+) -> Vec<Node> {
+    let mut calls = vec![0; layers.len()];
+    for node in &nodes {
+        calls[node.layer_index] += 1;
+    }
+    let mut reads: HashMap<(String, usize), usize> = HashMap::new();
+    for input in nodes
+        .iter()
+        .flat_map(|node| &node.inputs)
+        .chain(outputs.iter())
+    {
+        *reads.entry(owned_key(input)).or_default() += 1;
+    }
+    // Removed nodes with the node they were folded into.
+    let mut folded: HashMap<(String, usize), (String, usize)> = HashMap::new();
+    let redirect = |folded: &HashMap<_, (String, usize)>, reference: &mut TensorReference| {
+        if let Some((layer_name, node_index)) = folded.get(&owned_key(reference)) {
+            *reference = TensorReference::new(
+                layer_name.clone(),
+                *node_index,
+                reference.get_tensor_index(),
+            );
+        }
+    };
+    // Position in `kept` of the node producing each tensor.
+    let mut producers: HashMap<(String, usize), usize> = HashMap::new();
+    let mut kept: Vec<Node> = Vec::with_capacity(nodes.len());
+    for mut node in nodes {
+        for input in &mut node.inputs {
+            redirect(&folded, input);
+        }
+        let key = (node.layer_name.clone(), node.node_index);
+        if let [input] = node.inputs.as_slice() {
+            let input_key = owned_key(input);
+            let producer = producers
+                .get(&input_key)
+                .map(|&position| kept[position].layer_index);
+            let foldable = producer.is_some_and(|producer| {
+                input.get_tensor_index() == 0
+                    && calls[producer] == 1
+                    && calls[node.layer_index] == 1
+                    && reads[&input_key] == 1
+                    && fold_into(layers, producer, node.layer_index)
+            });
+            if foldable {
+                reads.insert(input_key.clone(), reads.get(&key).copied().unwrap_or(0));
+                folded.insert(key, input_key);
+                continue;
+            }
+        }
+        producers.insert(key, kept.len());
+        kept.push(node);
+    }
+    for output in outputs {
+        redirect(&folded, output);
+    }
+    kept
+}
+
+/// Folds the layer at `normalization`, if it is a `BatchNormalization`, into the one at `index`.
+fn fold_into(layers: &mut [ModelLayer], index: usize, normalization: usize) -> bool {
+    let (layer, normalization) = match index.cmp(&normalization) {
+        std::cmp::Ordering::Less => {
+            let (head, tail) = layers.split_at_mut(normalization);
+            (&mut head[index], &tail[0])
+        }
+        std::cmp::Ordering::Greater => {
+            let (head, tail) = layers.split_at_mut(index);
+            (&mut tail[0], &head[normalization])
+        }
+        std::cmp::Ordering::Equal => return false,
+    };
+    match normalization.layer.batch_normalization() {
+        Some(batch_normalization) => layer.layer.fold_batch_normalization(batch_normalization),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::{
+        AttentionOptions, BatchNormalization, Dense, Layer, LayerNormalization, Merge,
+        MergeOperation, MultiHeadAttention,
+    };
+    use crate::{Matrix, Vector};
+    use assert_approx_eq::assert_approx_eq;
+
+    fn node(layer_name: &str, input: &str) -> Node {
+        Node {
+            layer_index: 0,
+            layer_name: String::from(layer_name),
+            node_index: 0,
+            inputs: vec![TensorReference::new(String::from(input), 0, 0)],
+        }
+    }
+
+    #[test]
+    fn test_sort_nodes() {
+        let inputs = vec![TensorReference::new(String::from("input"), 0, 0)];
+        let nodes = vec![
+            node("dense_2", "dense_1"),
+            node("dense_1", "input"),
+            node("dense_3", "dense_2"),
+        ];
+
+        let sorted = sort_nodes(nodes, &inputs).unwrap();
+
+        let order: Vec<&str> = sorted.iter().map(|node| node.layer_name.as_str()).collect();
+        assert_eq!(order, vec!["dense_1", "dense_2", "dense_3"]);
+    }
+
+    #[test]
+    fn test_sort_nodes_with_cycle() {
+        let inputs = vec![TensorReference::new(String::from("input"), 0, 0)];
+        let nodes = vec![node("dense_1", "dense_2"), node("dense_2", "dense_1")];
+
+        assert!(matches!(
+            sort_nodes(nodes, &inputs),
+            Err(ModelError::ConfigurationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_compute_named_with_two_branches() {
+        let reference = |name: &str| TensorReference::new(String::from(name), 0, 0);
+        let scale = |factor: f32| -> Box<dyn Layer> {
+            Box::new(Dense::new(
+                Matrix::eye(2) * factor,
+                Some(Vector::zeros(2)),
+                None,
+            ))
+        };
+        let model = FunctionalModel {
+            layers: ModelLayer::numbered(vec![scale(2.0), scale(3.0)]),
+            nodes: vec![
+                Node {
+                    layer_index: 0,
+                    layer_name: String::from("double"),
+                    node_index: 0,
+                    inputs: vec![reference("input")],
+                },
+                Node {
+                    layer_index: 1,
+                    layer_name: String::from("triple"),
+                    node_index: 0,
+                    inputs: vec![reference("double")],
+                },
+            ],
+            inputs: vec![reference("input")],
+            outputs: vec![reference("double"), reference("triple")],
+            metadata: None,
+        };
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[2]), vec![1.0, 2.0]).unwrap();
+
+        let outputs = model
+            .compute_named(HashMap::from([(String::from("input"), input)]))
+            .unwrap();
+
+        assert_eq!(outputs["double"].as_slice().unwrap(), &[2.0, 4.0]);
+        assert_eq!(outputs["triple"].as_slice().unwrap(), &[6.0, 12.0]);
+    }
+
+    #[test]
+    fn test_residual_connection() {
+        let reference = |name: &str| TensorReference::new(String::from(name), 0, 0);
+        let model = FunctionalModel {
+            layers: ModelLayer::numbered(vec![
+                Box::new(Dense::new(
+                    Matrix::eye(2) * 2.0,
+                    Some(Vector::zeros(2)),
+                    None,
+                )),
+                Box::new(Merge::new(MergeOperation::Add)),
+            ]),
+            nodes: vec![
+                Node {
+                    layer_index: 0,
+                    layer_name: String::from("double"),
+                    node_index: 0,
+                    inputs: vec![reference("input")],
+                },
+                Node {
+                    layer_index: 1,
+                    layer_name: String::from("add"),
+                    node_index: 0,
+                    inputs: vec![reference("input"), reference("double")],
+                },
+            ],
+            inputs: vec![reference("input")],
+            outputs: vec![reference("add")],
+            metadata: None,
+        };
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[2]), vec![1.0, 2.0]).unwrap();
+
+        let outputs = model.compute(vec![input]).unwrap();
+
+        assert_eq!(outputs[0].as_slice().unwrap(), &[3.0, 6.0]);
+    }
+
+    #[test]
+    fn test_fold_batch_normalization() {
+        let reference = |name: &str| TensorReference::new(String::from(name), 0, 0);
+        let node = |layer_index: usize, layer_name: &str, input: &str| Node {
+            layer_index,
+            layer_name: String::from(layer_name),
+            node_index: 0,
+            inputs: vec![reference(input)],
+        };
+        let batch_normalization = || -> Box<dyn Layer> {
+            Box::new(BatchNormalization::new(
+                -1,
+                None,
+                Some(Vector::from_vec(vec![2.0, 1.0])),
+                Some(Vector::from_vec(vec![0.5, 0.0])),
+                Vector::from_vec(vec![1.0, -1.0]),
+                Vector::from_vec(vec![4.0, 0.25]),
+                0.0,
+            ))
+        };
+        let model = || FunctionalModel {
+            layers: ModelLayer::numbered(vec![
+                Box::new(Dense::new(
+                    Matrix::eye(2) * 2.0,
+                    Some(Vector::ones(2)),
+                    None,
+                )),
+                batch_normalization(),
+                Box::new(Dense::new(
+                    Matrix::eye(2) * 3.0,
+                    Some(Vector::zeros(2)),
+                    None,
+                )),
+                batch_normalization(),
+            ]),
+            nodes: vec![
+                node(0, "double", "input"),
+                node(1, "normalization", "double"),
+                node(2, "triple", "input"),
+                // `triple` is an output as well, so it can't absorb the normalization.
+                node(3, "normalization_2", "triple"),
+            ],
+            inputs: vec![reference("input")],
+            outputs: vec![
+                reference("normalization"),
+                reference("normalization_2"),
+                reference("triple"),
+            ],
+            metadata: None,
+        };
+        let input = NArray::from_shape_vec(ndarray::IxDyn(&[1, 2]), vec![1.0, -2.0]).unwrap();
+        let expected = model().predict_batch(vec![input.clone()]).unwrap();
+
+        let mut folded = model();
+        folded.nodes = fold_batch_normalization(
+            &mut folded.layers,
+            std::mem::take(&mut folded.nodes),
+            &mut folded.outputs,
+        );
+
+        let names: Vec<&str> = folded
+            .nodes
+            .iter()
+            .map(|node| node.layer_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["double", "triple", "normalization_2"]);
+        assert_eq!(
+            folded.get_output_names(),
+            vec!["double", "normalization_2", "triple"]
+        );
+        let outputs = folded.predict_batch(vec![input]).unwrap();
+        for (output, expected) in outputs.iter().zip(&expected) {
+            for (actual, expected) in output.iter().zip(expected) {
+                assert_approx_eq!(actual, expected, 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transformer_encoder_block() {
+        let reference = |name: &str| TensorReference::new(String::from(name), 0, 0);
+        let dense = |rows: usize, columns: usize, scale: f32| {
+            Dense::new(
+                Matrix::from_shape_fn((rows, columns), |(i, j)| scale * (i + 2 * j) as f32),
+                Some(Vector::zeros(columns)),
+                None,
+            )
+        };
+        let node = |layer_index: usize, layer_name: &str, inputs: &[&str]| Node {
+            layer_index,
+            layer_name: String::from(layer_name),
+            node_index: 0,
+            inputs: inputs.iter().map(|input| reference(input)).collect(),
+        };
+        let layer_normalization =
+            || -> Box<dyn Layer> { Box::new(LayerNormalization::new(vec![-1], 1e-6, None, None)) };
+        let model = FunctionalModel {
+            layers: ModelLayer::numbered(vec![
+                Box::new(MultiHeadAttention::new(
+                    2,
+                    dense(4, 4, 0.1),
+                    dense(4, 4, 0.2),
+                    dense(4, 6, 0.1),
+                    dense(6, 4, 0.1),
+                    AttentionOptions::default(),
+                )),
+                Box::new(Merge::new(MergeOperation::Add)),
+                layer_normalization(),
+                Box::new(dense(4, 4, 0.5)),
+            ]),
+            nodes: vec![
+                node(0, "attention", &["input", "input"]),
+                node(1, "residual", &["input", "attention"]),
+                node(2, "normalization", &["residual"]),
+                node(3, "feed_forward", &["normalization"]),
+            ],
+            inputs: vec![reference("input")],
+            outputs: vec![reference("normalization"), reference("feed_forward")],
+            metadata: None,
+        };
+        let input = NArray::from_shape_fn(ndarray::IxDyn(&[2, 3, 4]), |index| {
+            (index[0] + index[1] * index[2]) as f32 * 0.3 - 1.0
+        });
+
+        let outputs = model.predict_batch(vec![input]).unwrap();
+
+        assert_eq!(outputs[1].shape(), &[2, 3, 4]);
+        for row in outputs[0].lanes(Axis(2)) {
+            assert!(row.sum().abs() < 1e-4);
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use crate::configuration::{Config, Metadata};
+use crate::model::sequential::ModelError;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+use tempfile::NamedTempFile;
+use zip::ZipArchive;
+
+const CONFIG_FILE: &str = "config.json";
+const METADATA_FILE: &str = "metadata.json";
+const WEIGHTS_FILE: &str = "model.weights.h5";
+
+/// Content of a `.keras` zip archive produced by `model.save("model.keras")`.
+pub struct KerasArchive {
+    config: Config,
+    metadata: Metadata,
+    // Declared before `_weights_file` so the hdf5 handle is closed before the file is removed.
+    weights: hdf5::File,
+    _weights_file: NamedTempFile,
+}
+
+impl KerasArchive {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::from_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader<R: Read + Seek>(reader: R) -> Result<Self, ModelError> {
+        let mut archive = ZipArchive::new(reader)?;
+        let config = serde_json::from_reader(archive.by_name(CONFIG_FILE)?)?;
+        let metadata = serde_json::from_reader(archive.by_name(METADATA_FILE)?)?;
+
+        // hdf5 can only open files from disk, so the weights are unpacked to a temporary file.
+        let mut weights_file = NamedTempFile::new()?;
+        io::copy(
+            &mut archive.by_name(WEIGHTS_FILE)?,
+            weights_file.as_file_mut(),
+        )?;
+        let weights = hdf5::File::open(weights_file.path())?;
+
+        Ok(Self {
+            config,
+            metadata,
+            weights,
+            _weights_file: weights_file,
+        })
+    }
+
+    pub fn get_config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn get_weights(&self) -> &hdf5::File {
+        &self.weights
+    }
+
+    pub fn into_metadata(self) -> Metadata {
+        self.metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::FileOptions;
+    use zip::{result::ZipError, ZipWriter};
+
+    const CONFIG: &str = r#"{
+        "module": "keras",
+        "class_name": "Sequential",
+        "config": {"name": "sequential", "layers": []},
+        "registered_name": null,
+        "build_config": null,
+        "compile_config": null
+    }"#;
+    const METADATA: &str = r#"{"keras_version": "3.1.1", "date_saved": "2024-03-30@18:20:11"}"#;
+
+    fn archive_with(entries: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, FileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        let mut cursor = writer.finish().unwrap();
+        cursor.set_position(0);
+        cursor
+    }
+
+    #[test]
+    fn test_missing_weights() {
+        let reader = archive_with(&[(CONFIG_FILE, CONFIG), (METADATA_FILE, METADATA)]);
+
+        let result = KerasArchive::from_reader(reader);
+
+        assert!(matches!(
+            result,
+            Err(ModelError::ArchiveError(ZipError::FileNotFound))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_metadata() {
+        let reader = archive_with(&[(CONFIG_FILE, CONFIG), (METADATA_FILE, "{}")]);
+
+        let result = KerasArchive::from_reader(reader);
+
+        assert!(matches!(result, Err(ModelError::ParsingError(_))));
+    }
+}
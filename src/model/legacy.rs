@@ -0,0 +1,138 @@
+use crate::configuration::{Config, Metadata};
+use crate::model::sequential::ModelError;
+use hdf5::types::{FixedAscii, TypeDescriptor, VarLenUnicode};
+
+const MODEL_CONFIG_ATTRIBUTE: &str = "model_config";
+const KERAS_VERSION_ATTRIBUTE: &str = "keras_version";
+
+/// Checks whether `file` is a full-model file written by Keras 2 / tf.keras `model.save("x.h5")`.
+pub fn is_legacy_model(file: &hdf5::File) -> bool {
+    has_attribute(file, MODEL_CONFIG_ATTRIBUTE)
+}
+
+/// Parses the architecture stored as JSON in the root `model_config` attribute.
+pub fn read_config(file: &hdf5::File) -> Result<Config, ModelError> {
+    if !is_legacy_model(file) {
+        return Err(ModelError::ConfigurationError(
+            "Failed to find model_config attribute",
+        ));
+    }
+    let model_config = read_string_attribute(file, MODEL_CONFIG_ATTRIBUTE)?;
+    Ok(serde_json::from_str(&model_config)?)
+}
+
+pub fn read_metadata(file: &hdf5::File) -> Result<Option<Metadata>, ModelError> {
+    if !has_attribute(file, KERAS_VERSION_ATTRIBUTE) {
+        return Ok(None);
+    }
+    let keras_version = read_string_attribute(file, KERAS_VERSION_ATTRIBUTE)?;
+    Ok(Some(Metadata::new(keras_version, None)))
+}
+
+fn has_attribute(location: &hdf5::Location, name: &str) -> bool {
+    location
+        .attr_names()
+        .map(|names| names.iter().any(|attribute| attribute == name))
+        .unwrap_or(false)
+}
+
+/// h5py stores `bytes` attributes as fixed-length strings and `str` ones as variable-length
+/// strings, Keras 2 used both depending on the version.
+fn read_string_attribute(location: &hdf5::Location, name: &str) -> hdf5::Result<String> {
+    let attribute = location.attr(name)?;
+    match attribute.dtype()?.to_descriptor()? {
+        TypeDescriptor::VarLenAscii | TypeDescriptor::VarLenUnicode => Ok(attribute
+            .read_scalar::<VarLenUnicode>()?
+            .as_str()
+            .to_owned()),
+        TypeDescriptor::FixedAscii(len) | TypeDescriptor::FixedUnicode(len) => match len {
+            0..=4096 => read_fixed_string::<4096>(&attribute),
+            4097..=65536 => read_fixed_string::<65536>(&attribute),
+            65537..=1048576 => read_fixed_string::<1048576>(&attribute),
+            _ => read_fixed_string::<16777216>(&attribute),
+        },
+        descriptor => Err(format!("Attribute {name} is {descriptor}, not a string").into()),
+    }
+}
+
+fn read_fixed_string<const N: usize>(attribute: &hdf5::Attribute) -> hdf5::Result<String> {
+    // `read_raw` keeps the (possibly large) buffer on the heap.
+    let values = attribute.read_raw::<FixedAscii<N>>()?;
+    Ok(values
+        .first()
+        .map(|value| value.as_str().to_owned())
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    const CONFIG: &str =
+        r#"{"class_name": "Sequential", "config": {"name": "sequential", "layers": []}}"#;
+
+    fn empty_file() -> (hdf5::File, NamedTempFile) {
+        let temp = NamedTempFile::new().unwrap();
+        (hdf5::File::create(temp.path()).unwrap(), temp)
+    }
+
+    /// Writes `value` the way h5py stores a `str` attribute.
+    fn write_variable_length(file: &hdf5::File, name: &str, value: &str) {
+        file.new_attr::<VarLenUnicode>()
+            .create(name)
+            .unwrap()
+            .write_scalar(&value.parse::<VarLenUnicode>().unwrap())
+            .unwrap();
+    }
+
+    /// Writes `value` the way h5py stores a `bytes` attribute.
+    fn write_fixed_length(file: &hdf5::File, name: &str, value: &str) {
+        file.new_attr::<FixedAscii<128>>()
+            .create(name)
+            .unwrap()
+            .write_scalar(&FixedAscii::<128>::from_ascii(value).unwrap())
+            .unwrap();
+    }
+
+    #[test]
+    fn variable_length_attributes() {
+        let (file, _temp) = empty_file();
+        write_variable_length(&file, MODEL_CONFIG_ATTRIBUTE, CONFIG);
+        write_variable_length(&file, KERAS_VERSION_ATTRIBUTE, "2.15.0");
+
+        let config = read_config(&file).unwrap();
+        let metadata = read_metadata(&file).unwrap().unwrap();
+
+        assert!(is_legacy_model(&file));
+        assert_eq!(config.get_class_name(), "Sequential");
+        assert_eq!(metadata.get_keras_version(), "2.15.0");
+    }
+
+    #[test]
+    fn fixed_length_attributes() {
+        let (file, _temp) = empty_file();
+        write_fixed_length(&file, MODEL_CONFIG_ATTRIBUTE, CONFIG);
+
+        let config = read_config(&file).unwrap();
+
+        assert_eq!(config.get_class_name(), "Sequential");
+        assert_eq!(
+            read_string_attribute(&file, MODEL_CONFIG_ATTRIBUTE).unwrap(),
+            CONFIG
+        );
+        assert!(read_metadata(&file).unwrap().is_none());
+    }
+
+    #[test]
+    fn weights_only_file() {
+        let (file, _temp) = empty_file();
+
+        assert!(!is_legacy_model(&file));
+        assert!(matches!(
+            read_config(&file),
+            Err(ModelError::ConfigurationError(_))
+        ));
+        assert!(read_metadata(&file).unwrap().is_none());
+    }
+}
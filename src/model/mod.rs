@@ -1 +1,7 @@
+mod builder;
+pub mod functional;
+pub mod keras_archive;
+pub mod legacy;
+mod saved_model;
 pub mod sequential;
+pub mod summary;
@@ -0,0 +1,61 @@
+use crate::configuration::{Config, Metadata};
+use crate::model::keras_archive::KerasArchive;
+use crate::model::legacy;
+use crate::model::sequential::ModelError;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+/// Architecture, weights and metadata of a model saved by Keras, shared by the loaders of
+/// every model type.
+pub(crate) enum SavedModel {
+    /// `.keras` archive, whose weights are unpacked to a temporary file.
+    Archive(KerasArchive),
+    /// `.h5` file written by Keras 2 / tf.keras, holding both the config and the weights.
+    Legacy {
+        config: Config,
+        file: hdf5::File,
+        metadata: Option<Metadata>,
+    },
+}
+
+impl SavedModel {
+    pub fn from_keras_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::from_keras_reader(BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_keras_reader<R: Read + Seek>(reader: R) -> Result<Self, ModelError> {
+        Ok(SavedModel::Archive(KerasArchive::from_reader(reader)?))
+    }
+
+    pub fn from_h5_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        let file = hdf5::File::open(path)?;
+        Ok(SavedModel::Legacy {
+            config: legacy::read_config(&file)?,
+            metadata: legacy::read_metadata(&file)?,
+            file,
+        })
+    }
+
+    pub fn get_config(&self) -> &Config {
+        match self {
+            SavedModel::Archive(archive) => archive.get_config(),
+            SavedModel::Legacy { config, .. } => config,
+        }
+    }
+
+    pub fn get_weights(&self) -> &hdf5::File {
+        match self {
+            SavedModel::Archive(archive) => archive.get_weights(),
+            SavedModel::Legacy { file, .. } => file,
+        }
+    }
+
+    /// Drops the weights, which are only needed while the model is built.
+    pub fn into_metadata(self) -> Option<Metadata> {
+        match self {
+            SavedModel::Archive(archive) => Some(archive.into_metadata()),
+            SavedModel::Legacy { metadata, .. } => metadata,
+        }
+    }
+}
@@ -1,50 +1,699 @@
-use crate::configuration::{Config, LayerType};
-use crate::layer::{Dense, Flatten, Layer};
+use crate::configuration::layers::LayerConfigError;
+use crate::configuration::{self, Config, Metadata};
+use crate::layer::{format_shape, Layer, LayerError, Mask};
+use crate::model::builder::build_layer;
+use crate::model::saved_model::SavedModel;
+use crate::model::summary::{LayerSummary, ModelSummary};
 use crate::NArray;
+use ndarray::Axis;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use std::fmt;
+use std::io::{Read, Seek};
+use std::path::Path;
 use thiserror::Error;
 
 pub struct SequentialModel {
-    layers: Vec<Box<dyn Layer>>,
+    layers: Vec<ModelLayer>,
+    /// Batched input shape from the config, unknown when it doesn't record one.
+    input_shape: Option<Vec<Option<usize>>>,
+    /// Recorded before folding, so it lists every layer of the config.
+    summary: ModelSummary,
+    metadata: Option<Metadata>,
+}
+
+/// Position, name and type of a layer in the model config, to tell which one failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerContext {
+    index: usize,
+    name: String,
+    class_name: String,
+}
+
+impl LayerContext {
+    pub fn new(index: usize, name: String, class_name: String) -> Self {
+        Self {
+            index,
+            name,
+            class_name,
+        }
+    }
+
+    pub fn from_config(index: usize, layer_config: &configuration::Layer) -> Self {
+        Self::new(
+            index,
+            layer_config.get_name().unwrap_or("<unnamed>").to_owned(),
+            layer_config.get_class_name_str(),
+        )
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_class_name(&self) -> &str {
+        &self.class_name
+    }
+
+    fn build_error(&self, error: ModelError) -> ModelError {
+        ModelError::LayerBuildError {
+            layer: Box::new(self.clone()),
+            error: Box::new(error),
+        }
+    }
+
+    fn shape_error(&self, error: LayerError) -> ModelError {
+        ModelError::LayerShapeError {
+            layer: Box::new(self.clone()),
+            error,
+        }
+    }
+
+    fn compute_error(&self, input_shapes: Vec<Vec<usize>>, error: LayerError) -> ModelError {
+        ModelError::LayerComputeError {
+            layer: Box::new(self.clone()),
+            input_shapes,
+            error,
+        }
+    }
+}
+
+impl fmt::Display for LayerContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {} ({})", self.index, self.name, self.class_name)
+    }
+}
+
+/// A built layer along with the config entry it comes from.
+pub(crate) struct ModelLayer {
+    pub(crate) context: LayerContext,
+    pub(crate) layer: Box<dyn Layer>,
+    /// Static shape of the first output, once inferred from the model input shape.
+    pub(crate) output_shape: Option<Vec<Option<usize>>>,
+}
+
+impl ModelLayer {
+    pub(crate) fn new(context: LayerContext, layer: Box<dyn Layer>) -> Self {
+        Self {
+            context,
+            layer,
+            output_shape: None,
+        }
+    }
+
+    /// Builds the layer at `index` of the config, `None` for layers without computation.
+    pub(crate) fn build(
+        index: usize,
+        layer_config: &configuration::Layer,
+        file: &hdf5::File,
+    ) -> Result<Option<Self>, ModelError> {
+        let context = LayerContext::from_config(index, layer_config);
+        match build_layer(layer_config, file) {
+            Ok(layer) => Ok(layer.map(|layer| Self::new(context, layer))),
+            Err(source) => Err(context.build_error(source)),
+        }
+    }
+
+    /// Wraps `layers` as if they were the whole config, for tests.
+    #[cfg(test)]
+    pub(crate) fn numbered(layers: Vec<Box<dyn Layer>>) -> Vec<Self> {
+        layers
+            .into_iter()
+            .enumerate()
+            .map(|(index, layer)| {
+                let context =
+                    LayerContext::new(index, format!("layer_{index}"), String::from("Layer"));
+                Self::new(context, layer)
+            })
+            .collect()
+    }
+
+    /// [`Layer::compute_all_masked`], naming the layer and the input shape on failure.
+    pub(crate) fn compute_all_masked(
+        &self,
+        incoming: NArray,
+        mask: Option<&Mask>,
+    ) -> Result<Vec<NArray>, ModelError> {
+        let shape = incoming.shape().to_vec();
+        self.layer
+            .compute_all_masked(incoming, mask)
+            .map_err(|source| self.context.compute_error(vec![shape], source))
+    }
+
+    /// [`Layer::compute_multiple`], naming the layer and the input shapes on failure.
+    pub(crate) fn compute_multiple(
+        &self,
+        incoming: Vec<NArray>,
+    ) -> Result<Vec<NArray>, ModelError> {
+        let shapes = incoming
+            .iter()
+            .map(|input| input.shape().to_vec())
+            .collect();
+        self.layer
+            .compute_multiple(incoming)
+            .map_err(|source| self.context.compute_error(shapes, source))
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum ModelError {
-    #[error("Can't open hdf5 file ")]
+    #[error("Can't parse the model config: {0}")]
     ParsingError(#[from] serde_json::Error),
-    #[error("Can't open hdf5 file ")]
+    #[error("Can't read the hdf5 file: {0}")]
     LayerParseError(#[from] hdf5::Error),
-    #[error("Can't open hdf5 file ")]
+    #[error("Can't read the weights at {path}: {error}")]
+    WeightsError { path: String, error: hdf5::Error },
+    #[error("Incompatible array shapes: {0}")]
     ComputationError(#[from] ndarray::ShapeError),
-    #[error("Can't open hdf5 file ")]
+    #[error(
+        "Model expects inputs of shape {}, got {actual:?}",
+        format_shape(expected)
+    )]
+    InputShapeMismatch {
+        expected: Vec<Option<usize>>,
+        actual: Vec<usize>,
+    },
+    #[error("{0}")]
+    LayerError(#[from] LayerError),
+    #[error("{0}")]
+    LayerConfigError(#[from] LayerConfigError),
+    #[error("Can't build layer {layer}: {error}")]
+    LayerBuildError {
+        layer: Box<LayerContext>,
+        error: Box<ModelError>,
+    },
+    #[error("Layer {layer} doesn't fit the output of the previous one: {error}")]
+    LayerShapeError {
+        layer: Box<LayerContext>,
+        error: LayerError,
+    },
+    #[error("Layer {layer} failed on inputs of shape {input_shapes:?}: {error}")]
+    LayerComputeError {
+        layer: Box<LayerContext>,
+        input_shapes: Vec<Vec<usize>>,
+        error: LayerError,
+    },
+    #[error("Invalid model config: {0}")]
     ConfigurationError(&'static str),
+    #[error("Model takes {expected} inputs, got {actual}")]
+    InputCountMismatch { expected: usize, actual: usize },
+    #[error("Model input {0} is missing")]
+    MissingInput(String),
+    #[error("Can't read keras archive")]
+    ArchiveError(#[from] zip::result::ZipError),
+    #[error("Can't read file")]
+    IoError(#[from] std::io::Error),
 }
 
 impl SequentialModel {
     pub fn from_config_and_hdf5(config: Config, file: &hdf5::File) -> Result<Self, ModelError> {
+        Self::build(&config, file)
+    }
+
+    /// Loads the model from a `.keras` archive without unpacking it manually.
+    pub fn from_keras_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_keras_file(path)?)
+    }
+
+    pub fn from_keras_reader<R: Read + Seek>(reader: R) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_keras_reader(reader)?)
+    }
+
+    /// Loads a model saved by Keras 2 / tf.keras with `model.save("model.h5")`.
+    pub fn from_h5_file<P: AsRef<Path>>(path: P) -> Result<Self, ModelError> {
+        Self::load(SavedModel::from_h5_file(path)?)
+    }
+
+    fn load(saved_model: SavedModel) -> Result<Self, ModelError> {
+        let mut model = Self::build(saved_model.get_config(), saved_model.get_weights())?;
+        model.metadata = saved_model.into_metadata();
+        Ok(model)
+    }
+
+    fn build(config: &Config, file: &hdf5::File) -> Result<Self, ModelError> {
         let mut layers = Vec::new();
-        for layer_config in config.get_layers() {
-            let layer: Box<dyn Layer> = match layer_config.get_class_name() {
-                LayerType::Dense => {
-                    let activation =
-                        serde_json::from_value(layer_config.get_property("activation").clone())?;
-                    let layer_name = layer_config.get_property("name").as_str().ok_or(
-                        ModelError::ConfigurationError("Failed to find layer name"),
-                    )?;
-                    let dense = Dense::from_hdf5(file, layer_name, activation)?;
-                    Box::new(dense)
-                }
-                LayerType::Flatten => Box::new(Flatten),
-                LayerType::InputLayer => continue,
-            };
-            layers.push(layer);
-        }
-        Ok(SequentialModel { layers })
-    }
-
-    pub fn compute(&self, mut input: NArray) -> Result<NArray, ModelError> {
+        for (index, layer_config) in config.get_layers().iter().enumerate() {
+            if let Some(layer) = ModelLayer::build(index, layer_config, file)? {
+                layers.push(layer);
+            }
+        }
+        let input_shape = config.get_layers().first().and_then(|layer_config| {
+            layer_config
+                .get_batch_input_shape()
+                .or_else(|| layer_config.get_input_shape())
+        });
+        Self::new(layers, input_shape)
+    }
+
+    /// Infers the output shapes of `layers` when the input shape is known, summarizes them and
+    /// folds the `BatchNormalization` layers that can be.
+    fn new(
+        mut layers: Vec<ModelLayer>,
+        input_shape: Option<Vec<Option<usize>>>,
+    ) -> Result<Self, ModelError> {
+        if let Some(input_shape) = &input_shape {
+            infer_shapes(&mut layers, input_shape)?;
+        }
+        Ok(SequentialModel {
+            summary: summarize(&layers),
+            layers: fold_batch_normalization(layers),
+            input_shape,
+            metadata: None,
+        })
+    }
+
+    /// Batched shape the model takes, `None` for sizes such as the batch that may vary.
+    /// Unknown when the config doesn't record it.
+    pub fn input_shape(&self) -> Option<&[Option<usize>]> {
+        self.input_shape.as_deref()
+    }
+
+    /// Batched shape of the model output, inferred from [`SequentialModel::input_shape`].
+    pub fn output_shape(&self) -> Option<&[Option<usize>]> {
+        match self.layers.last() {
+            Some(layer) => layer.output_shape.as_deref(),
+            None => self.input_shape(),
+        }
+    }
+
+    /// Metadata of the `.keras` archive or `.h5` file the model was loaded from, if any.
+    pub fn get_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    /// Version of Keras that saved the model, unknown when built from a bare weights file.
+    pub fn get_keras_version(&self) -> Option<&str> {
+        self.metadata.as_ref().map(Metadata::get_keras_version)
+    }
+
+    /// Layers of the model with their output shapes and parameter counts. Displaying it
+    /// writes the Keras `model.summary()` table. `BatchNormalization` layers folded into the
+    /// preceding layer are still listed, as in Keras.
+    pub fn summary(&self) -> &ModelSummary {
+        &self.summary
+    }
+
+    /// Runs a single sample, given without the batch axis.
+    pub fn compute(&self, input: NArray) -> Result<NArray, ModelError> {
+        Ok(self
+            .predict_batch(input.insert_axis(Axis(0)))?
+            .remove_axis(Axis(0)))
+    }
+
+    /// Runs a `[batch, ...]` array through the model, returning the `[batch, ...]` outputs.
+    pub fn predict_batch(&self, mut input: NArray) -> Result<NArray, ModelError> {
+        if let Some(expected) = &self.input_shape {
+            let fits = expected.len() == input.ndim()
+                && expected
+                    .iter()
+                    .zip(input.shape())
+                    .all(|(expected, actual)| expected.is_none_or(|expected| expected == *actual));
+            if !fits {
+                return Err(ModelError::InputShapeMismatch {
+                    expected: expected.clone(),
+                    actual: input.shape().to_vec(),
+                });
+            }
+        }
+        let mut mask = None;
         for layer in &self.layers {
-            input = layer.compute(input)?;
+            let output_mask = layer.layer.compute_mask(&input, mask.as_ref());
+            input = layer
+                .compute_all_masked(input, mask.as_ref())?
+                .swap_remove(0);
+            mask = output_mask;
         }
         Ok(input)
     }
+
+    /// Same as [`SequentialModel::predict_batch`], splitting the batch in chunks of
+    /// `chunk_size` samples run on the threads of `pool`. The outputs keep the input order.
+    pub fn predict_parallel(
+        &self,
+        input: NArray,
+        chunk_size: usize,
+        pool: &ThreadPool,
+    ) -> Result<NArray, ModelError> {
+        match input.shape().first() {
+            None => {
+                return Err(ModelError::InputShapeMismatch {
+                    expected: self.input_shape.clone().unwrap_or_else(|| vec![None]),
+                    actual: Vec::new(),
+                })
+            }
+            // There are no chunk outputs to concatenate, the model gives the empty output.
+            Some(0) => return self.predict_batch(input),
+            Some(_) => {}
+        }
+        let chunks: Vec<_> = input.axis_chunks_iter(Axis(0), chunk_size.max(1)).collect();
+        let outputs = pool.install(|| {
+            chunks
+                .into_par_iter()
+                .map(|chunk| self.predict_batch(chunk.to_owned()))
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+        let views: Vec<_> = outputs.iter().map(NArray::view).collect();
+        Ok(ndarray::concatenate(Axis(0), &views)?)
+    }
+}
+
+/// Propagates `input_shape` through `layers`, checking that each one fits the output of the
+/// previous one and recording its output shape.
+fn infer_shapes(
+    layers: &mut [ModelLayer],
+    input_shape: &[Option<usize>],
+) -> Result<(), ModelError> {
+    let mut shape = input_shape.to_vec();
+    for layer in layers {
+        shape = layer
+            .layer
+            .output_shape(&shape)
+            .map_err(|source| layer.context.shape_error(source))?;
+        layer.output_shape = Some(shape.clone());
+    }
+    Ok(())
+}
+
+fn summarize(layers: &[ModelLayer]) -> ModelSummary {
+    ModelSummary {
+        layers: layers
+            .iter()
+            .map(|layer| LayerSummary {
+                name: layer.context.get_name().to_owned(),
+                class_name: layer.context.get_class_name().to_owned(),
+                output_shape: layer.output_shape.clone(),
+                params: layer.layer.param_count(),
+            })
+            .collect(),
+    }
+}
+
+/// Removes every `BatchNormalization` that can be folded into the layer preceding it.
+fn fold_batch_normalization(layers: Vec<ModelLayer>) -> Vec<ModelLayer> {
+    let mut folded: Vec<ModelLayer> = Vec::with_capacity(layers.len());
+    for layer in layers {
+        if let (Some(batch_normalization), Some(previous)) =
+            (layer.layer.batch_normalization(), folded.last_mut())
+        {
+            if previous.layer.fold_batch_normalization(batch_normalization) {
+                continue;
+            }
+        }
+        folded.push(layer);
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layer::ParamCount;
+    use crate::layer::{
+        ActivationFunction, BatchNormalization, DataFormat, Dense, Embedding, Flatten,
+        GlobalPooling, PoolingMode, RecurrentOptions, SimpleRnn,
+    };
+    use crate::{Matrix, Vector};
+    use std::sync::Arc;
+
+    fn model() -> SequentialModel {
+        SequentialModel::new(
+            ModelLayer::numbered(vec![
+                Box::new(Flatten::default()),
+                Box::new(Dense::new(
+                    Matrix::from_shape_vec((4, 2), vec![1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0])
+                        .unwrap(),
+                    Some(Vector::zeros(2)),
+                    Some(ActivationFunction::SoftMax),
+                )),
+            ]),
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_predict_batch_matches_single_samples() {
+        let model = model();
+        let batch = NArray::from_shape_vec(
+            ndarray::IxDyn(&[3, 2, 2]),
+            vec![1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0, 2.0, 0.0, 1.0],
+        )
+        .unwrap();
+
+        let predictions = model.predict_batch(batch.clone()).unwrap();
+
+        assert_eq!(predictions.shape(), &[3, 2]);
+        for (sample, prediction) in batch.axis_iter(Axis(0)).zip(predictions.axis_iter(Axis(0))) {
+            let single = model.compute(sample.to_owned()).unwrap();
+            assert_eq!(single, prediction);
+        }
+        assert_eq!(predictions[[1, 0]], 0.5);
+    }
+
+    #[test]
+    fn test_predict_parallel_keeps_order() {
+        let model = Arc::new(model());
+        let batch = NArray::from_shape_fn(ndarray::IxDyn(&[10, 2, 2]), |index| {
+            (index[0] as f32 - 5.0) * index[2] as f32
+        });
+        let expected = model.predict_batch(batch.clone()).unwrap();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+
+        let shared = Arc::clone(&model);
+        let predictions = std::thread::spawn(move || shared.predict_parallel(batch, 3, &pool))
+            .join()
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(predictions, expected);
+    }
+
+    #[test]
+    fn test_predict_parallel_edge_cases() {
+        let model = model();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+
+        let empty = model
+            .predict_parallel(NArray::zeros(ndarray::IxDyn(&[0, 2, 2])), 3, &pool)
+            .unwrap();
+        let error = model
+            .predict_parallel(NArray::zeros(ndarray::IxDyn(&[])), 3, &pool)
+            .unwrap_err();
+
+        assert_eq!(empty.shape(), &[0, 2]);
+        assert_eq!(
+            error.to_string(),
+            "Model expects inputs of shape (None,), got []"
+        );
+    }
+
+    #[test]
+    fn test_fold_batch_normalization_after_linear_layers_only() {
+        let batch_normalization = || -> Box<dyn Layer> {
+            Box::new(BatchNormalization::new(
+                -1,
+                None,
+                None,
+                None,
+                Vector::zeros(2),
+                Vector::ones(2),
+                0.0,
+            ))
+        };
+        let dense = |activation| -> Box<dyn Layer> {
+            Box::new(Dense::new(
+                Matrix::eye(2),
+                Some(Vector::zeros(2)),
+                activation,
+            ))
+        };
+        let layers = ModelLayer::numbered(vec![
+            batch_normalization(),
+            dense(None),
+            batch_normalization(),
+            dense(Some(ActivationFunction::ReLu)),
+            batch_normalization(),
+        ]);
+
+        let folded = fold_batch_normalization(layers);
+
+        let is_batch_normalization: Vec<bool> = folded
+            .iter()
+            .map(|layer| layer.layer.batch_normalization().is_some())
+            .collect();
+        assert_eq!(is_batch_normalization, vec![true, false, false, true]);
+    }
+
+    #[test]
+    fn test_embedding_mask_reaches_recurrent_layer() {
+        let model = SequentialModel::new(
+            ModelLayer::numbered(vec![
+                Box::new(Embedding::new(
+                    Matrix::from_shape_vec((3, 1), vec![0.0, 1.0, 10.0]).unwrap(),
+                    true,
+                )),
+                Box::new(SimpleRnn::new(
+                    Matrix::ones((1, 1)),
+                    Matrix::ones((1, 1)),
+                    Some(Vector::ones(1)),
+                    ActivationFunction::Linear,
+                    RecurrentOptions::default(),
+                )),
+            ]),
+            None,
+        )
+        .unwrap();
+        let tokens =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 3]), vec![1.0, 2.0, 0.0, 0.0, 0.0, 2.0])
+                .unwrap();
+
+        let output = model.predict_batch(tokens).unwrap();
+
+        // The padding is skipped: (1 + 1) + 10 + 1 and 10 + 1.
+        assert_eq!(output.as_slice().unwrap(), &[13.0, 11.0]);
+    }
+
+    #[test]
+    fn test_embedding_mask_reaches_global_average_pooling() {
+        let model = SequentialModel::new(
+            ModelLayer::numbered(vec![
+                Box::new(Embedding::new(
+                    Matrix::from_shape_vec((3, 1), vec![0.0, 1.0, 10.0]).unwrap(),
+                    true,
+                )),
+                Box::new(GlobalPooling::new(
+                    PoolingMode::Average,
+                    1,
+                    DataFormat::ChannelsLast,
+                    false,
+                )),
+            ]),
+            None,
+        )
+        .unwrap();
+        let tokens =
+            NArray::from_shape_vec(ndarray::IxDyn(&[2, 3]), vec![1.0, 2.0, 0.0, 0.0, 0.0, 2.0])
+                .unwrap();
+
+        let output = model.predict_batch(tokens).unwrap();
+
+        // The padding is left out of the averages: (1 + 10) / 2 and 10 / 1.
+        assert_eq!(output.as_slice().unwrap(), &[5.5, 10.0]);
+    }
+
+    #[test]
+    fn test_compute_error_names_the_layer_and_shape() {
+        let input = NArray::zeros(ndarray::IxDyn(&[1, 3]));
+
+        let error = model().predict_batch(input).unwrap_err();
+
+        let message = error.to_string();
+        assert!(message.starts_with("Layer #1 layer_1 (Layer) failed on inputs of shape [[1, 3]]"));
+        assert!(message.contains("expected 4 features"));
+        assert!(matches!(
+            error,
+            ModelError::LayerComputeError {
+                error: LayerError::IncompatibleInput { .. },
+                ..
+            }
+        ));
+        // The message already includes the layer error, it isn't repeated as the source.
+        assert!(std::error::Error::source(&error).is_none());
+    }
+
+    #[test]
+    fn test_infer_shapes() {
+        let mut model = model();
+        let input_shape = [None, Some(2), Some(2)];
+
+        infer_shapes(&mut model.layers, &input_shape).unwrap();
+        model.input_shape = Some(input_shape.to_vec());
+
+        assert_eq!(model.output_shape(), Some(&[None, Some(2)][..]));
+        let error = model
+            .predict_batch(NArray::zeros(ndarray::IxDyn(&[1, 3, 2])))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Model expects inputs of shape (None, 2, 2), got [1, 3, 2]"
+        );
+    }
+
+    #[test]
+    fn test_infer_shapes_checks_the_weights() {
+        let mut model = model();
+
+        let error = infer_shapes(&mut model.layers, &[None, Some(3), Some(2)]).unwrap_err();
+
+        assert!(error
+            .to_string()
+            .starts_with("Layer #1 layer_1 (Layer) doesn't fit the output of the previous one"));
+        assert!(matches!(
+            error,
+            ModelError::LayerShapeError {
+                error: LayerError::IncompatibleShape { .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_summary() {
+        let model =
+            SequentialModel::new(model().layers, Some(vec![None, Some(2), Some(2)])).unwrap();
+
+        let summary = model.summary();
+
+        let output_shapes: Vec<_> = summary
+            .layers
+            .iter()
+            .map(|layer| layer.output_shape.clone())
+            .collect();
+        assert_eq!(
+            output_shapes,
+            vec![Some(vec![None, Some(4)]), Some(vec![None, Some(2)])]
+        );
+        assert_eq!(summary.layers[1].name, "layer_1");
+        assert_eq!(summary.total_params(), ParamCount::trainable(10));
+    }
+
+    #[test]
+    fn test_summary_lists_folded_batch_normalization() {
+        let layers = ModelLayer::numbered(vec![
+            Box::new(Dense::new(Matrix::eye(2), Some(Vector::zeros(2)), None)),
+            Box::new(BatchNormalization::new(
+                -1,
+                None,
+                Some(Vector::ones(2)),
+                Some(Vector::zeros(2)),
+                Vector::zeros(2),
+                Vector::ones(2),
+                0.0,
+            )),
+        ]);
+
+        let model = SequentialModel::new(layers, Some(vec![None, Some(2)])).unwrap();
+
+        assert_eq!(model.layers.len(), 1);
+        let summary = model.summary();
+        assert_eq!(summary.layers.len(), 2);
+        assert_eq!(summary.layers[1].params.non_trainable, 4);
+        assert_eq!(
+            summary.total_params(),
+            ParamCount {
+                trainable: 10,
+                non_trainable: 4,
+            }
+        );
+    }
 }
@@ -0,0 +1,144 @@
+use crate::layer::{format_shape, ParamCount};
+use std::fmt;
+
+/// A layer of a [`ModelSummary`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayerSummary {
+    pub name: String,
+    pub class_name: String,
+    /// Unknown when the model config doesn't record its input shape.
+    pub output_shape: Option<Vec<Option<usize>>>,
+    pub params: ParamCount,
+}
+
+/// Layers of a model with their output shapes and parameter counts. Displaying it writes the
+/// table of Keras `model.summary()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSummary {
+    pub layers: Vec<LayerSummary>,
+}
+
+impl ModelSummary {
+    pub fn total_params(&self) -> ParamCount {
+        self.layers.iter().map(|layer| layer.params).sum()
+    }
+}
+
+impl fmt::Display for ModelSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let header = [
+            String::from("Layer (type)"),
+            String::from("Output Shape"),
+            String::from("Param #"),
+        ];
+        let rows: Vec<[String; 3]> = self
+            .layers
+            .iter()
+            .map(|layer| {
+                [
+                    format!("{} ({})", layer.name, layer.class_name),
+                    layer
+                        .output_shape
+                        .as_deref()
+                        .map_or_else(|| String::from("?"), format_shape),
+                    layer.params.total().to_string(),
+                ]
+            })
+            .collect();
+        let width = |column: usize| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        };
+        let (name_width, shape_width) = (width(0) + 4, width(1) + 4);
+        let line_width = name_width + shape_width + width(2);
+        let write_row = |f: &mut fmt::Formatter<'_>, [name, shape, params]: &[String; 3]| {
+            writeln!(f, "{name:<name_width$}{shape:<shape_width$}{params}")
+        };
+
+        writeln!(f, "{}", "_".repeat(line_width))?;
+        write_row(f, &header)?;
+        writeln!(f, "{}", "=".repeat(line_width))?;
+        for (index, row) in rows.iter().enumerate() {
+            if index > 0 {
+                writeln!(f, "{}", "_".repeat(line_width))?;
+            }
+            write_row(f, row)?;
+        }
+        writeln!(f, "{}", "=".repeat(line_width))?;
+        let total = self.total_params();
+        writeln!(f, "Total params: {}", group_thousands(total.total()))?;
+        writeln!(f, "Trainable params: {}", group_thousands(total.trainable))?;
+        writeln!(
+            f,
+            "Non-trainable params: {}",
+            group_thousands(total.non_trainable)
+        )?;
+        write!(f, "{}", "_".repeat(line_width))
+    }
+}
+
+/// Writes `count` with thousands separators, as Keras does for the totals.
+fn group_thousands(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_thousands_like_keras() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(850), "850");
+        assert_eq!(group_thousands(7850), "7,850");
+        assert_eq!(group_thousands(1234567), "1,234,567");
+    }
+
+    #[test]
+    fn summary_table() {
+        let summary = ModelSummary {
+            layers: vec![
+                LayerSummary {
+                    name: String::from("dense"),
+                    class_name: String::from("Dense"),
+                    output_shape: Some(vec![None, Some(10)]),
+                    params: ParamCount::trainable(7850),
+                },
+                LayerSummary {
+                    name: String::from("batch_normalization"),
+                    class_name: String::from("BatchNormalization"),
+                    output_shape: None,
+                    params: ParamCount {
+                        trainable: 20,
+                        non_trainable: 20,
+                    },
+                },
+            ],
+        };
+
+        let expected = "\
+___________________________________________________________________
+Layer (type)                                Output Shape    Param #
+===================================================================
+dense (Dense)                               (None, 10)      7850
+___________________________________________________________________
+batch_normalization (BatchNormalization)    ?               40
+===================================================================
+Total params: 7,890
+Trainable params: 7,870
+Non-trainable params: 20
+___________________________________________________________________";
+        assert_eq!(summary.to_string(), expected);
+    }
+}
@@ -0,0 +1,260 @@
+/// How the variables of a layer are stored in the weights file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightsLayout {
+    /// `layers/<layer>/vars/<index>`, written by Keras 3.
+    Keras3,
+    /// `model_weights/<layer>/<layer>/<variable>:0`, written by Keras 2 and tf.keras.
+    Legacy,
+}
+
+// Keras 3 joins the group names with the platform separator, so files saved on Windows
+// contain `layers\<layer>` links instead of nested groups.
+const LAYER_ROOTS: [(&str, WeightsLayout); 5] = [
+    ("layers/", WeightsLayout::Keras3),
+    (r"layers\", WeightsLayout::Keras3),
+    ("_layer_checkpoint_dependencies/", WeightsLayout::Keras3),
+    ("model_weights/", WeightsLayout::Legacy),
+    ("", WeightsLayout::Legacy),
+];
+
+/// Variables of a single layer inside a weights file.
+#[derive(Clone)]
+pub struct LayerWeights {
+    group: hdf5::Group,
+    layout: WeightsLayout,
+}
+
+impl LayerWeights {
+    pub fn from_file(file: &hdf5::File, layer_name: &str) -> hdf5::Result<Self> {
+        for (root, layout) in LAYER_ROOTS {
+            let path = format!("{root}{layer_name}");
+            if file.link_exists(&path) {
+                return Ok(Self {
+                    group: file.group(&path)?,
+                    layout,
+                });
+            }
+        }
+        Err(format!("Failed to find weights of layer {layer_name}").into())
+    }
+
+    pub fn get_layout(&self) -> WeightsLayout {
+        self.layout
+    }
+
+    /// Path of the layer group inside the file.
+    pub fn get_path(&self) -> String {
+        self.group.name()
+    }
+
+    /// Where [`LayerWeights::variable`] looks for a variable, for error messages. Keras 2
+    /// variables may sit in a subgroup.
+    pub fn variable_path(&self, index: usize, name: &str) -> String {
+        match self.layout {
+            WeightsLayout::Keras3 => format!("{}/vars/{index}", self.group.name()),
+            WeightsLayout::Legacy => format!("{}/{name}", self.group.name()),
+        }
+    }
+
+    /// Variables of a layer nested in this one, such as the `cell` of a recurrent layer.
+    /// Keras 2 files are searched by variable name, so they keep using this layer's group.
+    pub fn sublayer(&self, name: &str) -> hdf5::Result<Self> {
+        let group = match self.layout {
+            WeightsLayout::Keras3 => self.group.group(name)?,
+            WeightsLayout::Legacy => self.group.clone(),
+        };
+        Ok(Self {
+            group,
+            layout: self.layout,
+        })
+    }
+
+    /// Variables of the layer held in the `attribute` of a wrapper such as `Bidirectional`.
+    /// Keras 2 files group them by the name of the wrapped layer, so the first subgroup
+    /// starting with `legacy_prefix` is used instead, or this group for an empty prefix.
+    pub fn wrapped(&self, attribute: &str, legacy_prefix: &str) -> hdf5::Result<Self> {
+        let group = match self.layout {
+            WeightsLayout::Keras3 => self.group.group(attribute)?,
+            WeightsLayout::Legacy if legacy_prefix.is_empty() => self.group.clone(),
+            WeightsLayout::Legacy => find_group(&self.group, legacy_prefix)?.ok_or_else(|| {
+                format!("Failed to find {legacy_prefix}* in {}", self.group.name())
+            })?,
+        };
+        Ok(Self {
+            group,
+            layout: self.layout,
+        })
+    }
+
+    /// Returns the variable stored at `index` (Keras 3) or called `name` (Keras 2).
+    pub fn variable(&self, index: usize, name: &str) -> hdf5::Result<hdf5::Dataset> {
+        match self.layout {
+            WeightsLayout::Keras3 => self.group.dataset(&format!("vars/{index}")),
+            WeightsLayout::Legacy => find_dataset(&self.group, name)?
+                .ok_or_else(|| format!("Failed to find {name} in {}", self.group.name()).into()),
+        }
+    }
+}
+
+/// Searches `group` and its subgroups for a dataset called `name` or `name:0`.
+fn find_dataset(group: &hdf5::Group, name: &str) -> hdf5::Result<Option<hdf5::Dataset>> {
+    for dataset in group.datasets()? {
+        let path = dataset.name();
+        let dataset_name = path.rsplit('/').next().unwrap_or_default();
+        if dataset_name == name || dataset_name.strip_suffix(":0") == Some(name) {
+            return Ok(Some(dataset));
+        }
+    }
+    for subgroup in group.groups()? {
+        if let Some(dataset) = find_dataset(&subgroup, name)? {
+            return Ok(Some(dataset));
+        }
+    }
+    Ok(None)
+}
+
+/// Searches the subgroups of `group` for one whose name starts with `prefix`.
+fn find_group(group: &hdf5::Group, prefix: &str) -> hdf5::Result<Option<hdf5::Group>> {
+    for subgroup in group.groups()? {
+        let path = subgroup.name();
+        if path
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .starts_with(prefix)
+        {
+            return Ok(Some(subgroup));
+        }
+        if let Some(found) = find_group(&subgroup, prefix)? {
+            return Ok(Some(found));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    /// Writes an hdf5 file holding the given `(path, values)` datasets, creating their groups.
+    /// The temporary file is returned along with the handle to keep it on disk.
+    fn file_with(datasets: &[(&str, &[f32])]) -> (hdf5::File, NamedTempFile) {
+        let temp = NamedTempFile::new().unwrap();
+        let file = hdf5::File::create(temp.path()).unwrap();
+        for (path, values) in datasets {
+            let (groups, name) = path.rsplit_once('/').unwrap();
+            let mut group: hdf5::Group = (*file).clone();
+            for segment in groups.split('/') {
+                group = if group.link_exists(segment) {
+                    group.group(segment).unwrap()
+                } else {
+                    group.create_group(segment).unwrap()
+                };
+            }
+            group
+                .new_dataset_builder()
+                .with_data(&ndarray::arr1(values))
+                .create(name)
+                .unwrap();
+        }
+        (file, temp)
+    }
+
+    fn read(dataset: hdf5::Result<hdf5::Dataset>) -> Vec<f32> {
+        dataset.unwrap().read_raw().unwrap()
+    }
+
+    #[test]
+    fn keras3_variables_by_index() {
+        let (file, _temp) = file_with(&[
+            ("layers/dense/vars/0", &[1.0, 2.0]),
+            ("layers/dense/vars/1", &[3.0]),
+        ]);
+
+        let weights = LayerWeights::from_file(&file, "dense").unwrap();
+
+        assert_eq!(weights.get_layout(), WeightsLayout::Keras3);
+        assert_eq!(weights.get_path(), "/layers/dense");
+        assert_eq!(weights.variable_path(1, "bias"), "/layers/dense/vars/1");
+        assert_eq!(read(weights.variable(1, "bias")), vec![3.0]);
+    }
+
+    #[test]
+    fn layer_roots_are_searched_in_order() {
+        let (file, _temp) = file_with(&[
+            (r"layers\conv/vars/0", &[1.0]),
+            ("model_weights/dense/dense/kernel:0", &[2.0]),
+            ("dense/dense/kernel:0", &[3.0]),
+            ("embedding/embedding/embeddings:0", &[4.0]),
+        ]);
+
+        let conv = LayerWeights::from_file(&file, "conv").unwrap();
+        let dense = LayerWeights::from_file(&file, "dense").unwrap();
+        let embedding = LayerWeights::from_file(&file, "embedding").unwrap();
+        let missing = LayerWeights::from_file(&file, "missing").err().unwrap();
+
+        assert_eq!(conv.get_layout(), WeightsLayout::Keras3);
+        assert_eq!(read(conv.variable(0, "kernel")), vec![1.0]);
+        assert_eq!(dense.get_layout(), WeightsLayout::Legacy);
+        assert_eq!(dense.get_path(), "/model_weights/dense");
+        assert_eq!(read(dense.variable(0, "kernel")), vec![2.0]);
+        assert_eq!(read(embedding.variable(0, "embeddings")), vec![4.0]);
+        assert_eq!(
+            missing.to_string(),
+            "Failed to find weights of layer missing"
+        );
+    }
+
+    #[test]
+    fn legacy_variables_by_name() {
+        let (file, _temp) = file_with(&[
+            (
+                "model_weights/lstm/lstm/lstm_cell/recurrent_kernel:0",
+                &[1.0],
+            ),
+            ("model_weights/lstm/lstm/lstm_cell/kernel:0", &[2.0]),
+            ("model_weights/lstm/lstm/lstm_cell/bias:0", &[3.0]),
+        ]);
+        let weights = LayerWeights::from_file(&file, "lstm").unwrap();
+
+        let cell = weights.sublayer("cell").unwrap();
+
+        assert_eq!(cell.get_path(), "/model_weights/lstm");
+        assert_eq!(read(cell.variable(0, "kernel")), vec![2.0]);
+        assert_eq!(read(cell.variable(1, "recurrent_kernel")), vec![1.0]);
+        assert_eq!(read(cell.variable(0, "bias")), vec![3.0]);
+        assert_eq!(cell.variable_path(2, "bias"), "/model_weights/lstm/bias");
+        assert_eq!(
+            cell.variable(3, "gamma").unwrap_err().to_string(),
+            "Failed to find gamma in /model_weights/lstm"
+        );
+    }
+
+    #[test]
+    fn find_dataset_and_group() {
+        let (file, _temp) = file_with(&[
+            ("bidirectional/forward_gru/gru_cell/kernel:0", &[1.0]),
+            ("bidirectional/backward_gru/gru_cell/kernel:0", &[2.0]),
+            ("bidirectional/backward_gru/gru_cell/bias", &[3.0]),
+        ]);
+        let root = file.group("bidirectional").unwrap();
+
+        let backward = find_group(&root, "backward_").unwrap().unwrap();
+
+        assert_eq!(backward.name(), "/bidirectional/backward_gru");
+        assert!(find_group(&root, "gru_").unwrap().is_some());
+        assert!(find_group(&root, "missing_").unwrap().is_none());
+        assert_eq!(
+            read(find_dataset(&backward, "kernel").map(Option::unwrap)),
+            vec![2.0]
+        );
+        assert_eq!(
+            read(find_dataset(&backward, "bias").map(Option::unwrap)),
+            vec![3.0]
+        );
+        assert!(find_dataset(&backward, "recurrent_kernel")
+            .unwrap()
+            .is_none());
+    }
+}